@@ -0,0 +1,84 @@
+use ethers_core::types::{Address, OtherFields, U256, U64};
+use serde::Deserialize;
+
+use crate::error::EncodeError;
+
+/// Withdrawal of a validator's stake, included in blocks since the Shanghai upgrade
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    pub index: U64,
+    pub validator_index: U64,
+    pub address: Address,
+    /// Amount withdrawn, in gwei
+    pub amount: U256,
+}
+
+/// Returns the withdrawals of a block, which `Block` keeps among its other fields.
+/// Returns `None` for blocks before the Shanghai upgrade.
+pub fn block_withdrawals(other: &OtherFields) -> Option<Result<Vec<Withdrawal>, EncodeError>> {
+    other
+        .get_deserialized("withdrawals")
+        .map(|withdrawals| withdrawals.map_err(|e| EncodeError::SerdeError(e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{block_withdrawals, Withdrawal};
+    use ethers_core::types::{Block, TxHash, U256};
+    use serde_json::json;
+
+    fn block(withdrawals: Option<serde_json::Value>) -> Block<TxHash> {
+        let mut block = json!({
+            "hash": "0xb4b3b8c1b0d5f1a8e6e7a4e4a3b0f5c2e7b8a9d0c1f2e3d4c5b6a7980f1e2d3c",
+            "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "stateRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "transactionsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "receiptsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "number": "0x10",
+            "gasUsed": "0x0",
+            "gasLimit": "0x1c9c380",
+            "extraData": "0x",
+            "timestamp": "0x64372ba7",
+            "difficulty": "0x0",
+            "uncles": [],
+            "transactions": [],
+            "baseFeePerGas": "0x7"
+        });
+        if let Some(withdrawals) = withdrawals {
+            block["withdrawals"] = withdrawals;
+        }
+        serde_json::from_value(block).unwrap()
+    }
+
+    #[test]
+    fn parse_block_withdrawals() {
+        let block = block(Some(json!([{
+            "index": "0x1",
+            "validatorIndex": "0x2a",
+            "address": "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1",
+            "amount": "0x3b9aca00"
+        }])));
+        let withdrawals = block_withdrawals(&block.other).unwrap().unwrap();
+        assert_eq!(
+            withdrawals,
+            [Withdrawal {
+                index: 1u64.into(),
+                validator_index: 42u64.into(),
+                address: "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1".parse().unwrap(),
+                amount: U256::from(1_000_000_000u64),
+            }]
+            .to_vec()
+        );
+    }
+
+    #[test]
+    fn block_without_withdrawals() {
+        assert!(block_withdrawals(&block(None).other).is_none());
+        assert!(block_withdrawals(&block(Some(json!([{ "index": "0x1" }]))).other)
+            .unwrap()
+            .is_err());
+    }
+}
@@ -2,76 +2,206 @@ use ethers_core::{
     abi::{
         Param, ParamType, Token, encode, HumanReadableParser,
         token::LenientTokenizer, token::Tokenizer,
-        Function, Abi, encode_packed
+        Function, Abi, encode_packed, Event, EventParam, Log, RawLog,
+        Component, RawAbi
     },
-    types::{Bytes}
+    types::{Bytes, H256},
+    utils::keccak256
 };
 
 use crate::error::EncodeError;
 use std::str::FromStr;
 
-pub fn encode_params(types: Vec<String>, values: Vec<String>) -> Vec<u8> {
+pub fn encode_params(types: Vec<String>, values: Vec<String>) -> Result<Vec<u8>, EncodeError> {
     let tokens: Vec<Token> = values.iter()
         .zip(types.iter())
         .map(|(arg, t)| {
-            let kind = HumanReadableParser::parse_type(&t).unwrap();
-            if let ParamType::Array(items) = &kind {
-                if let ParamType::Address = items.as_ref() {
-                    return LenientTokenizer::tokenize(&kind, arg.replace("\"", "").as_str()).unwrap();
-                }
-            }
-            if arg.starts_with("\"") && arg.ends_with("\"") {
-                return LenientTokenizer::tokenize(&kind, arg.replace("\"", "").as_str()).unwrap();
-            }
-            LenientTokenizer::tokenize(&kind, arg).unwrap()
+            let kind = HumanReadableParser::parse_type(t).map_err(|e| {
+                EncodeError::LexerError(format!("{:?}", e))
+            })?;
+            tokenize_value(&kind, arg)
         })
-        .collect();
-    let bytes = encode(&tokens);
-    bytes
+        .collect::<Result<_, _>>()?;
+    Ok(encode(&tokens))
 }
 
 pub fn encode_function(method: &str, args: &Vec<String>) -> Result<(Function, Bytes), EncodeError> {
     let function: Function = parse_method(method)?;
-    let tokens: Vec<Token> = tokenize_values(&args, &function.inputs);
+    let tokens: Vec<Token> = tokenize_values(&args, &function.inputs)?;
     let bytes: Bytes = function.encode_input(&tokens).map(Into::into)?;
     Ok((function, bytes))
 }
 
-pub fn decode_function(method: &str, data: Vec<u8>) -> Vec<Token> {
-    let function: Function = parse_method(method).unwrap();
+/// Decodes the arguments of a call to `method`. The 4 bytes selector is optional.
+pub fn decode_function(method: &str, data: Vec<u8>) -> Result<Vec<Token>, EncodeError> {
+    let function: Function = parse_method(method)?;
     let sig = function.short_signature();
-    let mut has_sig = false;
 
-    if data[0..4] == sig {
-        has_sig = true;
-    }
+    // ABI encoded arguments are 32 bytes words, so calldata is 4 bytes longer
+    let arg_bytes: &[u8] = match data.len() % 32 == 4 {
+        true if data[0..4] == sig => &data[4..],
+        true => {
+            return Err(EncodeError::InvalidInput(format!(
+                "Selector 0x{} of the data doesn't match 0x{} of {}",
+                hex::encode(&data[0..4]),
+                hex::encode(sig),
+                function.name
+            )))
+        }
+        false => &data[0..],
+    };
 
-    let arg_bytes: &[u8] = match has_sig {
-        true => &data[4..],
-        false => &data[0..]
+    Ok(function.decode_input(arg_bytes)?)
+}
+
+/// Decodes the data returned by a call to `method`
+pub fn decode_function_result(method: &str, data: Vec<u8>) -> Result<Vec<Token>, EncodeError> {
+    let function: Function = parse_method(method)?;
+    Ok(function.decode_output(&data)?)
+}
+
+/// Decodes ABI encoded `data` as values of the given types (e.g. "uint256", "address")
+pub fn decode_params(types: &[String], data: Vec<u8>) -> Result<Vec<Token>, EncodeError> {
+    let kinds: Vec<ParamType> = types
+        .iter()
+        .map(|t| {
+            HumanReadableParser::parse_type(t)
+                .map_err(|e| EncodeError::LexerError(format!("{:?}", e)))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ethers_core::abi::decode(&kinds, &data)?)
+}
+
+/// Decodes a log with the first non-anonymous event of `abi` whose signature matches the
+/// log's first topic. Returns `None` if no event of the ABI matches the log.
+///
+/// Indexed dynamic values (strings, bytes, arrays and tuples) are returned as the
+/// `FixedBytes` keccak256 hash stored in the topic.
+pub fn decode_log(abi: &Abi, topics: Vec<H256>, data: Vec<u8>) -> Result<Option<(Event, Log)>, EncodeError> {
+    let topic0 = match topics.first() {
+        Some(topic) => *topic,
+        None => return Ok(None),
     };
 
-    function.decode_input(arg_bytes).unwrap()
+    // events with the same signature may differ in which params are indexed
+    // (e.g. ERC20 and ERC721 `Transfer`), so the first one that parses wins
+    let mut last_error: Option<EncodeError> = None;
+    for event in abi.events().filter(|e| !e.anonymous && e.signature() == topic0) {
+        let raw_log = RawLog { topics: topics.clone(), data: data.clone() };
+        match event.parse_log(raw_log) {
+            Ok(log) => return Ok(Some((event.clone(), log))),
+            Err(e) => last_error = Some(e.into()),
+        }
+    }
+
+    match last_error {
+        Some(e) => Err(e),
+        None => Ok(None),
+    }
+}
+
+/// Returns the canonical signature of an event (e.g. `Transfer(address,address,uint256)`)
+pub fn event_signature(event: &Event) -> String {
+    let types: Vec<String> = event.inputs.iter().map(|p| p.kind.to_string()).collect();
+    format!("{}({})", event.name, types.join(","))
+}
+
+/// Returns the canonical signature of a function (e.g. `transfer(address,uint256)`)
+pub fn function_signature(function: &Function) -> String {
+    let types: Vec<String> = function.inputs.iter().map(|p| p.kind.to_string()).collect();
+    format!("{}({})", function.name, types.join(","))
+}
+
+/// Encodes the topics of a log filter for `event`.
+///
+/// `values` are the values of the event's indexed params, in declaration order, and a `None`
+/// value matches any topic. Indexed dynamic values (strings, bytes, arrays and tuples) are
+/// hashed the same way the EVM does when the event is emitted.
+pub fn encode_event_topics(
+    event: &str,
+    values: &[Option<String>],
+) -> Result<Vec<Option<H256>>, EncodeError> {
+    let event: Event = parse_event(event)?;
+    let indexed: Vec<&EventParam> = event.inputs.iter().filter(|p| p.indexed).collect();
+    if values.len() > indexed.len() {
+        return Err(EncodeError::InvalidInput(format!(
+            "Too many values for event {}: expected at most {}, got {}",
+            event.name, indexed.len(), values.len()
+        )));
+    }
+
+    let mut topics: Vec<Option<H256>> = vec![];
+    if !event.anonymous {
+        topics.push(Some(event.signature()));
+    }
+    for (param, value) in indexed.iter().zip(values.iter()) {
+        let topic = match value {
+            Some(value) => Some(encode_topic(&tokenize_value(&param.kind, value)?)),
+            None => None,
+        };
+        topics.push(topic);
+    }
+
+    // trailing wildcards are implied
+    while let Some(None) = topics.last() {
+        topics.pop();
+    }
+    Ok(topics)
+}
+
+fn encode_topic(token: &Token) -> H256 {
+    match token {
+        Token::String(s) => H256::from(keccak256(s.as_bytes())),
+        Token::Bytes(bytes) => H256::from(keccak256(bytes)),
+        Token::Array(_) | Token::FixedArray(_) | Token::Tuple(_) => {
+            H256::from(keccak256(encode_in_place(token)))
+        }
+        _ => H256::from_slice(&encode(std::slice::from_ref(token))),
+    }
+}
+
+// Indexed arrays and structs are hashed from their "in-place" encoding: members are
+// concatenated without offsets or length prefixes, each padded to a multiple of 32 bytes
+fn encode_in_place(token: &Token) -> Vec<u8> {
+    match token {
+        Token::String(s) => pad_right(s.as_bytes()),
+        Token::Bytes(bytes) => pad_right(bytes),
+        Token::Array(items) | Token::FixedArray(items) | Token::Tuple(items) => {
+            items.iter().flat_map(encode_in_place).collect()
+        }
+        _ => encode(std::slice::from_ref(token)),
+    }
 }
 
-pub fn tokenize_values(values: &Vec<String>, params: &Vec<Param>) -> Vec<Token> {
+fn pad_right(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.resize(bytes.len().div_ceil(32) * 32, 0);
+    padded
+}
+
+pub fn tokenize_values(
+    values: &Vec<String>,
+    params: &Vec<Param>,
+) -> Result<Vec<Token>, EncodeError> {
     params
         .iter()
         .zip(values.iter())
-        .map(|(param, arg)| {
-            if let ParamType::Array(items) = &param.kind {
-                if let ParamType::Address = items.as_ref() {
-                    return LenientTokenizer::tokenize(&param.kind, arg.replace("\"", "").as_str()).unwrap();
-                }
-            }
-            if arg.starts_with("\"") && arg.ends_with("\"") {
-                return LenientTokenizer::tokenize(&param.kind, arg.replace("\"", "").as_str()).unwrap();
-            }
-            LenientTokenizer::tokenize(&param.kind, arg).unwrap()
-        })
+        .map(|(param, arg)| tokenize_value(&param.kind, arg))
         .collect()
 }
 
+pub fn tokenize_value(kind: &ParamType, value: &str) -> Result<Token, EncodeError> {
+    if let ParamType::Array(items) = kind {
+        if let ParamType::Address = items.as_ref() {
+            return Ok(LenientTokenizer::tokenize(kind, value.replace('"', "").as_str())?);
+        }
+    }
+    if value.starts_with('"') && value.ends_with('"') {
+        return Ok(LenientTokenizer::tokenize(kind, value.replace('"', "").as_str())?);
+    }
+    Ok(LenientTokenizer::tokenize(kind, value)?)
+}
+
 pub fn parse_method(method: &str) -> Result<Function, EncodeError> {
     let parse_result = HumanReadableParser::parse_function(method).map_err(|e| {
         EncodeError::LexerError(format!("{:?}", e))
@@ -89,15 +219,298 @@ pub fn parse_method(method: &str) -> Result<Function, EncodeError> {
         let abi: Abi = json_parse.map_err(|e| {
             EncodeError::SerdeError(format!("Failed to parse ABI. {:?}", e))
         })?;
-        let (_, functions): (&String, &Vec<Function>) = abi.functions.iter().next().unwrap();
-        let function: Function = functions.get(0).unwrap().clone();
-        Ok(function)
+        abi.functions()
+            .next()
+            .cloned()
+            .ok_or_else(|| EncodeError::InvalidInput("Function not found in ABI".to_string()))
+    }
+}
+
+/// Names of a function output and, for tuples, of its components
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ParamNames {
+    pub name: String,
+    pub components: Vec<ParamNames>,
+}
+
+/// Returns the names of the outputs of `method`. Human-readable signatures can't name tuple
+/// components, so those are only found when `method` is a JSON ABI fragment.
+pub fn parse_output_names(method: &str) -> Result<Vec<ParamNames>, EncodeError> {
+    let function: Function = parse_method(method)?;
+
+    let components: Vec<ParamNames> = if HumanReadableParser::parse_function(method).is_ok() {
+        vec![]
+    } else {
+        let abi_str = if method.starts_with('[') && method.ends_with(']') {
+            method.to_string()
+        } else {
+            format!("[{}]", method)
+        };
+        serde_json::from_str::<RawAbi>(&abi_str)
+            .ok()
+            .and_then(|raw| raw.into_iter().find(|item| item.type_field == "function"))
+            .map(|item| item.outputs.iter().map(component_names).collect())
+            .unwrap_or_default()
+    };
+
+    Ok(function
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(i, output)| ParamNames {
+            name: output.name.clone(),
+            components: components.get(i).map(|p| p.components.clone()).unwrap_or_default(),
+        })
+        .collect())
+}
+
+fn component_names(component: &Component) -> ParamNames {
+    ParamNames {
+        name: component.name.clone(),
+        components: component.components.iter().map(component_names).collect(),
     }
 }
 
-pub fn encode_packed_bytes(bytes: String) -> String {
-    let bytes = Bytes::from_str(&bytes).unwrap();
+pub fn parse_event(event: &str) -> Result<Event, EncodeError> {
+    let parse_result = HumanReadableParser::parse_event(event).map_err(|e| {
+        EncodeError::LexerError(format!("{:?}", e))
+    });
+    if parse_result.is_ok() {
+        return parse_result;
+    }
+
+    let abi_str = if event.starts_with('[') && event.ends_with(']') {
+        event.to_string()
+    } else {
+        format!("[{}]", event)
+    };
+    let abi: Abi = serde_json::from_str(&abi_str).map_err(|e| {
+        EncodeError::SerdeError(format!("Failed to parse ABI. {:?}", e))
+    })?;
+    abi.events()
+        .next()
+        .cloned()
+        .ok_or_else(|| EncodeError::InvalidInput("Event not found in ABI".to_string()))
+}
+
+pub fn encode_packed_bytes(bytes: String) -> Result<String, EncodeError> {
+    let bytes = Bytes::from_str(&bytes)
+        .map_err(|e| EncodeError::InvalidInput(format!("Invalid bytes {}: {}", bytes, e)))?;
     let token = Token::Bytes(bytes.to_vec());
-    let encoded = encode_packed(&[token]).unwrap();
-    format!("{}", Bytes::from(encoded))
+    let encoded = encode_packed(&[token])
+        .map_err(|e| EncodeError::InvalidInput(format!("Failed to pack bytes: {:?}", e)))?;
+    Ok(format!("{}", Bytes::from(encoded)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_function, decode_function_result, decode_log, decode_params, encode_event_topics,
+        encode_function, encode_params, event_signature, parse_output_names, EncodeError,
+        ParamNames,
+    };
+    use ethers_core::{
+        abi::{Abi, Token},
+        types::{Address, H256},
+        utils::keccak256,
+    };
+    use std::str::FromStr;
+
+    const ABI: &str = r#"[
+        {"anonymous":false,"inputs":[{"indexed":false,"name":"from","type":"address"},{"indexed":false,"name":"data","type":"uint256"}],"name":"DataSet","type":"event"},
+        {"anonymous":false,"inputs":[{"indexed":true,"name":"from","type":"address"},{"indexed":true,"name":"ipfsHash","type":"string"}],"name":"HashSet","type":"event"}
+    ]"#;
+
+    fn address_topic(address: Address) -> H256 {
+        let mut topic = [0u8; 32];
+        topic[12..].copy_from_slice(address.as_bytes());
+        H256::from(topic)
+    }
+
+    #[test]
+    fn decode_log_non_indexed() {
+        let abi: Abi = serde_json::from_str(ABI).unwrap();
+        let from = Address::from_str("0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1").unwrap();
+        let topic0 = H256::from(keccak256("DataSet(address,uint256)"));
+        let data = ethers_core::abi::encode(&[Token::Address(from), Token::Uint(100.into())]);
+
+        let (event, log) = decode_log(&abi, vec![topic0], data).unwrap().unwrap();
+        assert_eq!(event_signature(&event), "DataSet(address,uint256)");
+        assert_eq!(log.params[0].name, "from");
+        assert_eq!(log.params[0].value, Token::Address(from));
+        assert_eq!(log.params[1].name, "data");
+        assert_eq!(log.params[1].value, Token::Uint(100.into()));
+    }
+
+    #[test]
+    fn decode_log_indexed_dynamic_is_hash() {
+        let abi: Abi = serde_json::from_str(ABI).unwrap();
+        let from = Address::from_str("0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1").unwrap();
+        let topic0 = H256::from(keccak256("HashSet(address,string)"));
+        let hash = keccak256("QmHash");
+        let topics = vec![topic0, address_topic(from), H256::from(hash)];
+
+        let (_, log) = decode_log(&abi, topics, vec![]).unwrap().unwrap();
+        assert_eq!(log.params[0].value, Token::Address(from));
+        assert_eq!(log.params[1].value, Token::FixedBytes(hash.to_vec()));
+    }
+
+    #[test]
+    fn decode_log_unknown_event() {
+        let abi: Abi = serde_json::from_str(ABI).unwrap();
+        let topic0 = H256::from(keccak256("Unknown(uint256)"));
+        assert!(decode_log(&abi, vec![topic0], vec![]).unwrap().is_none());
+        assert!(decode_log(&abi, vec![], vec![]).unwrap().is_none());
+    }
+
+    #[test]
+    fn encode_event_topics_human_readable() {
+        let event = "event Transfer(address indexed from, address indexed to, uint256 value)";
+        let from = "0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1";
+        let topics = encode_event_topics(event, &[Some(from.to_string()), None]).unwrap();
+
+        let from = Address::from_str(from).unwrap();
+        assert_eq!(
+            topics,
+            vec![
+                Some(H256::from(keccak256("Transfer(address,address,uint256)"))),
+                Some(address_topic(from)),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_event_topics_json_with_wildcard() {
+        let event = r#"{"anonymous":false,"inputs":[{"indexed":true,"name":"from","type":"address"},{"indexed":true,"name":"ipfsHash","type":"string"}],"name":"HashSet","type":"event"}"#;
+        let topics = encode_event_topics(event, &[None, Some("QmHash".to_string())]).unwrap();
+
+        assert_eq!(
+            topics,
+            vec![
+                Some(H256::from(keccak256("HashSet(address,string)"))),
+                None,
+                Some(H256::from(keccak256("QmHash"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_event_topics_indexed_array() {
+        let event = "event Set(uint256[] indexed values, bytes32 indexed key)";
+        let topics = encode_event_topics(event, &[Some("[1,2]".to_string())]).unwrap();
+
+        let mut encoded = [0u8; 64];
+        encoded[31] = 1;
+        encoded[63] = 2;
+        assert_eq!(topics[1], Some(H256::from(keccak256(encoded))));
+        assert_eq!(topics.len(), 2);
+    }
+
+    #[test]
+    fn encode_event_topics_too_many_values() {
+        let event = "event DataSet(address from, uint256 data)";
+        assert!(encode_event_topics(event, &[None]).is_err());
+    }
+
+    fn names(names: &[&str]) -> Vec<ParamNames> {
+        names
+            .iter()
+            .map(|n| ParamNames { name: n.to_string(), components: vec![] })
+            .collect()
+    }
+
+    #[test]
+    fn parse_output_names_human_readable() {
+        let method = "function getMultiNamed() public view returns ((string,uint256,uint8) memory obj, uint8[6] memory array1d, string memory str)";
+        let outputs = parse_output_names(method).unwrap();
+
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[0], ParamNames { name: "obj".to_string(), components: vec![] });
+        assert_eq!(outputs[1].name, "array1d");
+        assert_eq!(outputs[2].name, "str");
+    }
+
+    #[test]
+    fn parse_output_names_json() {
+        let method = r#"{"inputs":[],"name":"getJobs","outputs":[{"components":[{"internalType":"address","name":"to","type":"address"},{"components":[{"internalType":"uint256","name":"amount","type":"uint256"},{"internalType":"bool","name":"paid","type":"bool"}],"internalType":"struct SimpleStorage.Part[]","name":"parts","type":"tuple[]"}],"internalType":"struct SimpleStorage.Job[]","name":"jobs","type":"tuple[]"}],"stateMutability":"view","type":"function"}"#;
+        let outputs = parse_output_names(method).unwrap();
+
+        assert_eq!(outputs[0].name, "jobs");
+        assert_eq!(outputs[0].components[0].name, "to");
+        assert_eq!(outputs[0].components[1].name, "parts");
+        assert_eq!(outputs[0].components[1].components, names(&["amount", "paid"]));
+    }
+
+    #[test]
+    fn decode_function_data() {
+        let method = "function transfer(address to, uint256 amount)";
+        let args = vec![
+            "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1".to_string(),
+            "1000".to_string(),
+        ];
+        let (_, data) = encode_function(method, &args).unwrap();
+
+        let expected = vec![
+            Token::Address(args[0].parse::<Address>().unwrap()),
+            Token::Uint(1000.into()),
+        ];
+        assert_eq!(decode_function(method, data.to_vec()).unwrap(), expected);
+        // the selector is optional
+        assert_eq!(decode_function(method, data[4..].to_vec()).unwrap(), expected);
+    }
+
+    #[test]
+    fn decode_function_short_data() {
+        let method = "function transfer(address to, uint256 amount)";
+        assert!(decode_function(method, vec![0xa9, 0x05]).is_err());
+        assert!(decode_function(method, vec![]).is_err());
+    }
+
+    #[test]
+    fn decode_function_other_selector() {
+        let args = vec![
+            "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1".to_string(),
+            "1000".to_string(),
+        ];
+        let (_, data) = encode_function("function approve(address to, uint256 amount)", &args)
+            .unwrap();
+        let method = "function transfer(address to, uint256 amount)";
+        assert!(matches!(
+            decode_function(method, data.to_vec()),
+            Err(EncodeError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn decode_function_result_data() {
+        let method = "function balanceOf(address owner) view returns (uint256)";
+        let data = encode_params(vec!["uint256".to_string()], vec!["42".to_string()]).unwrap();
+        assert_eq!(
+            decode_function_result(method, data).unwrap(),
+            vec![Token::Uint(42.into())]
+        );
+        assert!(decode_function_result(method, vec![0; 16]).is_err());
+    }
+
+    #[test]
+    fn decode_params_data() {
+        let types = vec!["string".to_string(), "bool".to_string()];
+        let values = vec!["hello".to_string(), "true".to_string()];
+        let data = encode_params(types.clone(), values).unwrap();
+        assert_eq!(
+            decode_params(&types, data).unwrap(),
+            vec![Token::String("hello".to_string()), Token::Bool(true)]
+        );
+        assert!(decode_params(&["uint257".to_string()], vec![]).is_err());
+    }
+
+    #[test]
+    fn malformed_arguments() {
+        let method = "function transfer(address to, uint256 amount)";
+        let args = vec!["0xinvalid".to_string(), "1".to_string()];
+        assert!(encode_function(method, &args).is_err());
+        assert!(encode_params(vec!["uint256".to_string()], vec!["abc".to_string()]).is_err());
+        // the JSON ABI only has events
+        assert!(encode_function(ABI, &Vec::new()).is_err());
+    }
 }
@@ -14,6 +14,15 @@ pub enum EncodeError {
     /// Error type from abi parsing
     #[error("SerdeError Error: {0:?}")]
     SerdeError(String),
+    /// Error type from invalid input values
+    #[error("InvalidInput Error: {0:?}")]
+    InvalidInput(String),
+    /// Error type from EIP-712 typed data parsing and hashing
+    #[error("TypedData Error: {0:?}")]
+    TypedDataError(String),
+    /// Error type from signature parsing and recovery
+    #[error("Signature Error: {0:?}")]
+    SignatureError(String),
 }
 
 impl From<ethers_core::abi::Error> for EncodeError {
@@ -0,0 +1,288 @@
+use ethers_core::types::{FeeHistory, U256};
+use ethers_core::utils::EIP1559_FEE_ESTIMATION_DEFAULT_PRIORITY_FEE;
+
+/// Presets of `FeePolicy`, trading off fees for inclusion speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSpeed {
+    Slow,
+    Standard,
+    Fast,
+}
+
+/// How EIP-1559 fees are estimated from the fee history of the latest blocks
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeePolicy {
+    /// Number of blocks of the fee history
+    pub block_count: u64,
+    /// Percentiles of the priority fees paid in each block, averaged to get the block's tip
+    pub reward_percentiles: Vec<f64>,
+    /// Multiplier of the base fee in the max fee per gas, covering base fee increases
+    /// until the transaction is included
+    pub base_fee_multiplier: f64,
+    /// Lowest max priority fee per gas
+    pub min_priority_fee: Option<U256>,
+    /// Highest max priority fee per gas
+    pub max_priority_fee: Option<U256>,
+}
+
+impl FeePolicy {
+    pub fn preset(speed: FeeSpeed) -> Self {
+        let (block_count, percentile, base_fee_multiplier) = match speed {
+            FeeSpeed::Slow => (20, 10.0, 1.25),
+            FeeSpeed::Standard => (10, 50.0, 2.0),
+            FeeSpeed::Fast => (5, 90.0, 2.5),
+        };
+        Self {
+            block_count,
+            reward_percentiles: vec![percentile],
+            base_fee_multiplier,
+            min_priority_fee: None,
+            max_priority_fee: None,
+        }
+    }
+
+    /// Estimates the max fee per gas and max priority fee per gas, from the base fee of the
+    /// latest block and the rewards of its fee history
+    pub fn estimate(&self, base_fee_per_gas: U256, rewards: &[Vec<U256>]) -> (U256, U256) {
+        let tips = rewards
+            .iter()
+            .filter(|block| !block.is_empty())
+            .map(|block| block.iter().fold(U256::zero(), |sum, r| sum + r) / block.len())
+            .collect();
+        let mut max_priority_fee_per_gas = median_tip(tips)
+            .unwrap_or_else(|| U256::from(EIP1559_FEE_ESTIMATION_DEFAULT_PRIORITY_FEE));
+        if let Some(min) = self.min_priority_fee {
+            max_priority_fee_per_gas = max_priority_fee_per_gas.max(min);
+        }
+        if let Some(max) = self.max_priority_fee {
+            max_priority_fee_per_gas = max_priority_fee_per_gas.min(max);
+        }
+
+        // the multiplier is applied in thousandths to keep the arithmetic in integers
+        let multiplier = U256::from((self.base_fee_multiplier * 1000.0).round() as u64);
+        let max_fee_per_gas = base_fee_per_gas * multiplier / 1000 + max_priority_fee_per_gas;
+        (max_fee_per_gas, max_priority_fee_per_gas)
+    }
+
+    /// Checks the reward percentiles, that the fee history has blocks, that the base fee
+    /// multiplier is positive and that the priority fee bounds are in order
+    pub fn check(&self) -> Result<(), String> {
+        if self.block_count == 0 {
+            return Err("Invalid block count: 0. The fee history needs a block".to_string());
+        }
+        check_reward_percentiles(&self.reward_percentiles)?;
+        if !(self.base_fee_multiplier.is_finite() && self.base_fee_multiplier > 0.0) {
+            return Err(format!(
+                "Invalid base fee multiplier: {}. It must be positive",
+                self.base_fee_multiplier
+            ));
+        }
+        if let (Some(min), Some(max)) = (self.min_priority_fee, self.max_priority_fee) {
+            if min > max {
+                return Err(format!("Invalid priority fees: min {} is above max {}", min, max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks reward percentiles like `eth_feeHistory` does: from 0 to 100, in increasing order
+pub fn check_reward_percentiles(percentiles: &[f64]) -> Result<(), String> {
+    for (i, percentile) in percentiles.iter().enumerate() {
+        if !(0.0..=100.0).contains(percentile) {
+            return Err(format!("Invalid reward percentile: {}. It must be 0 to 100", percentile));
+        }
+        if i > 0 && *percentile <= percentiles[i - 1] {
+            return Err(format!(
+                "Invalid reward percentiles: {} after {}. They must be in increasing order",
+                percentile,
+                percentiles[i - 1]
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        Self::preset(FeeSpeed::Standard)
+    }
+}
+
+/// State of the gas market at the newest block of a fee history
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasMarketSummary {
+    pub block_number: U256,
+    pub base_fee_per_gas: U256,
+    /// Base fee of the next block, computed by the node from the gas used by the newest block
+    pub next_base_fee_per_gas: U256,
+    /// Median priority fee paid at each reward percentile of the fee history
+    pub tips: Vec<(f64, U256)>,
+}
+
+/// Summarizes a fee history requested with `reward_percentiles`. Returns `None` if the blocks
+/// don't have a base fee (i.e. EIP-1559 is not activated).
+pub fn summarize_fee_history(
+    fee_history: &FeeHistory,
+    reward_percentiles: &[f64],
+) -> Option<GasMarketSummary> {
+    // base fees include the one of the block after the newest one
+    let len = fee_history.base_fee_per_gas.len();
+    if len < 2 || fee_history.base_fee_per_gas.iter().all(U256::is_zero) {
+        return None;
+    }
+    let tips = reward_percentiles
+        .iter()
+        .enumerate()
+        .map(|(i, percentile)| {
+            let rewards = fee_history
+                .reward
+                .iter()
+                .filter_map(|block| block.get(i).copied())
+                .collect();
+            (*percentile, median_tip(rewards).unwrap_or_default())
+        })
+        .collect();
+    Some(GasMarketSummary {
+        block_number: fee_history.oldest_block + len - 2,
+        base_fee_per_gas: fee_history.base_fee_per_gas[len - 2],
+        next_base_fee_per_gas: fee_history.base_fee_per_gas[len - 1],
+        tips,
+    })
+}
+
+/// Lowest fee bump, in percent, for nodes to accept a transaction replacing a pending one
+pub const MIN_REPLACEMENT_FEE_BUMP: u64 = 10;
+
+/// Bumps a fee by `percent`, rounding up so that the bump is never below the percentage
+pub fn bump_fee(fee: U256, percent: u64) -> U256 {
+    fee + (fee * percent + 99) / 100
+}
+
+// blocks without transactions report zero rewards, which don't reflect the market
+fn median_tip(mut tips: Vec<U256>) -> Option<U256> {
+    tips.retain(|tip| !tip.is_zero());
+    tips.sort();
+    tips.get(tips.len() / 2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bump_fee, check_reward_percentiles, summarize_fee_history, FeePolicy, FeeSpeed};
+    use ethers_core::types::{FeeHistory, U256};
+
+    fn gwei(value: u64) -> U256 {
+        U256::from(value) * 1_000_000_000u64
+    }
+
+    #[test]
+    fn estimate_median_tip() {
+        let rewards = [
+            vec![gwei(1)],
+            vec![gwei(0)],
+            vec![gwei(3)],
+            vec![gwei(2)],
+        ];
+        let (max_fee, priority_fee) = FeePolicy::default().estimate(gwei(10), &rewards);
+        assert_eq!(priority_fee, gwei(2));
+        assert_eq!(max_fee, gwei(22));
+    }
+
+    #[test]
+    fn estimate_averages_percentiles() {
+        let policy = FeePolicy {
+            reward_percentiles: vec![25.0, 75.0],
+            base_fee_multiplier: 1.5,
+            ..FeePolicy::preset(FeeSpeed::Fast)
+        };
+        let (max_fee, priority_fee) = policy.estimate(gwei(10), &[vec![gwei(1), gwei(3)]]);
+        assert_eq!(priority_fee, gwei(2));
+        assert_eq!(max_fee, gwei(17));
+    }
+
+    #[test]
+    fn estimate_caps_priority_fee() {
+        let rewards = [vec![gwei(5)]];
+        let policy = FeePolicy {
+            max_priority_fee: Some(gwei(2)),
+            ..FeePolicy::default()
+        };
+        assert_eq!(policy.estimate(gwei(10), &rewards).1, gwei(2));
+
+        let policy = FeePolicy {
+            min_priority_fee: Some(gwei(7)),
+            ..FeePolicy::default()
+        };
+        assert_eq!(policy.estimate(gwei(10), &rewards).1, gwei(7));
+    }
+
+    #[test]
+    fn estimate_without_rewards() {
+        let (_, priority_fee) = FeePolicy::preset(FeeSpeed::Slow).estimate(gwei(10), &[]);
+        assert_eq!(priority_fee, gwei(3));
+    }
+
+    #[test]
+    fn summarize_gas_market() {
+        let fee_history = FeeHistory {
+            base_fee_per_gas: vec![gwei(10), gwei(11), gwei(12)],
+            gas_used_ratio: vec![0.9, 0.95],
+            oldest_block: U256::from(100),
+            reward: vec![vec![gwei(1), gwei(4)], vec![gwei(2), gwei(0)]],
+        };
+        let summary = summarize_fee_history(&fee_history, &[10.0, 90.0]).unwrap();
+        assert_eq!(summary.block_number, U256::from(101));
+        assert_eq!(summary.base_fee_per_gas, gwei(11));
+        assert_eq!(summary.next_base_fee_per_gas, gwei(12));
+        assert_eq!(summary.tips, vec![(10.0, gwei(2)), (90.0, gwei(4))]);
+
+        let legacy = FeeHistory {
+            base_fee_per_gas: vec![U256::zero(), U256::zero()],
+            ..fee_history
+        };
+        assert!(summarize_fee_history(&legacy, &[50.0]).is_none());
+    }
+
+    #[test]
+    fn bump_replacement_fee() {
+        assert_eq!(bump_fee(gwei(10), 10), gwei(11));
+        assert_eq!(bump_fee(U256::from(15), 10), U256::from(17));
+        assert_eq!(bump_fee(gwei(2), 50), gwei(3));
+        assert_eq!(bump_fee(U256::zero(), 10), U256::zero());
+    }
+
+    #[test]
+    fn check_fee_policy() {
+        assert!(FeePolicy::preset(FeeSpeed::Fast).check().is_ok());
+        assert!(check_reward_percentiles(&[0.0, 25.5, 100.0]).is_ok());
+        assert!(check_reward_percentiles(&[50.0, 10.0]).is_err());
+        assert!(check_reward_percentiles(&[10.0, 10.0]).is_err());
+        assert!(check_reward_percentiles(&[101.0]).is_err());
+        assert!(check_reward_percentiles(&[-1.0]).is_err());
+
+        let policy = FeePolicy {
+            base_fee_multiplier: 0.0,
+            ..FeePolicy::default()
+        };
+        assert!(policy.check().is_err());
+
+        let policy = FeePolicy {
+            block_count: 0,
+            ..FeePolicy::default()
+        };
+        assert!(policy.check().is_err());
+
+        let policy = FeePolicy {
+            min_priority_fee: Some(U256::from(2)),
+            max_priority_fee: Some(U256::from(1)),
+            ..FeePolicy::default()
+        };
+        assert!(policy.check().is_err());
+        let policy = FeePolicy {
+            min_priority_fee: Some(U256::from(1)),
+            max_priority_fee: Some(U256::from(1)),
+            ..FeePolicy::default()
+        };
+        assert!(policy.check().is_ok());
+    }
+}
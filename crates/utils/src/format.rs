@@ -0,0 +1,144 @@
+use ethers_core::abi::Token;
+use ethers_core::types::{I256};
+use crate::ParamNames;
+use serde_json::{Map, Value};
+
+// format tokens to json
+pub fn format_tokens(tokens: &[Token]) -> String {
+    match tokens.len() {
+        0 => "".to_string(),
+        1 => format_token(&tokens[0]),
+        _ => format!("[{}]", format_arr(tokens)),
+    }
+}
+
+// format tokens to a json array, even when there is a single token
+pub fn format_tokens_array(tokens: &[Token]) -> String {
+    format!("[{}]", format_arr(tokens))
+}
+
+pub fn format_token(token: &Token) -> String {
+    match token {
+        Token::Bool(b) => format!("{}", b),
+        Token::String(s) => s.to_string(),
+        Token::Address(a) => format!("0x{:x}", a),
+        Token::Bytes(bytes)=> format!("0x{}", hex::encode(bytes)),
+        Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Uint(i)=> i.to_string(),
+        Token::Int(i) => I256::from_raw(*i).to_string(),
+        Token::Tuple(arr) => format!("[{}]", format_arr(arr)),
+        Token::Array(arr) => format!("[{}]", format_arr(arr)),
+        Token::FixedArray(arr) => format!("[{}]", format_arr(arr))
+    }
+}
+
+fn format_token_in_arr(token: &Token) -> String {
+    match token {
+        Token::String(s) => format!("\"{}\"", s),
+        Token::Address(a) => format!("\"0x{:x}\"", a),
+        Token::Bytes(bytes)=> format!("\"0x{}\"", hex::encode(bytes)),
+        Token::FixedBytes(bytes) => format!("\"0x{}\"", hex::encode(bytes)),
+        Token::Uint(i)=> {
+            if i.bits() > 64 { format!("\"{}\"", i) }
+            else { i.to_string() }
+        },
+        Token::Int(i) => {
+            let signed_i = I256::from_raw(*i);
+            if signed_i.bits() > 64  { format!("\"{}\"", signed_i) }
+            else { signed_i.to_string() }
+        },
+        _ => format_token(token)
+    }
+}
+
+fn format_arr(arr: &[Token]) -> String {
+    arr
+        .iter()
+        .map(format_token_in_arr)
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+// format tokens to json objects keyed by output and component names.
+// integers are always formatted as decimal strings, so big values keep their precision
+pub fn format_named_tokens(tokens: &[Token], names: &[ParamNames]) -> String {
+    let value = match tokens.len() {
+        0 => Value::Null,
+        1 if names.iter().all(|n| n.name.is_empty()) => named_value(&tokens[0], names.first()),
+        _ => named_values(tokens, names),
+    };
+    value.to_string()
+}
+
+// values with a name are keyed by it, the others by their position
+fn named_values(tokens: &[Token], names: &[ParamNames]) -> Value {
+    let map: Map<String, Value> = tokens
+        .iter()
+        .enumerate()
+        .map(|(i, token)| {
+            let names = names.get(i);
+            let key = match names {
+                Some(n) if !n.name.is_empty() => n.name.clone(),
+                _ => i.to_string(),
+            };
+            (key, named_value(token, names))
+        })
+        .collect();
+    Value::Object(map)
+}
+
+fn named_value(token: &Token, names: Option<&ParamNames>) -> Value {
+    match token {
+        Token::Bool(b) => Value::Bool(*b),
+        Token::String(s) => Value::String(s.to_string()),
+        Token::Address(a) => Value::String(format!("0x{:x}", a)),
+        Token::Bytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+        Token::FixedBytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+        Token::Uint(i) => Value::String(i.to_string()),
+        Token::Int(i) => Value::String(I256::from_raw(*i).to_string()),
+        Token::Tuple(arr) => match names {
+            Some(n) if !n.components.is_empty() => named_values(arr, &n.components),
+            _ => Value::Array(arr.iter().map(|t| named_value(t, None)).collect()),
+        },
+        // array elements share the component names of the array type
+        Token::Array(arr) | Token::FixedArray(arr) => {
+            Value::Array(arr.iter().map(|t| named_value(t, names)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_named_tokens, format_tokens};
+    use crate::ParamNames;
+    use ethers_core::abi::Token;
+
+    fn names(name: &str, components: &[&str]) -> ParamNames {
+        ParamNames {
+            name: name.to_string(),
+            components: components.iter().map(|c| names(c, &[])).collect(),
+        }
+    }
+
+    #[test]
+    fn format_named_struct_and_arrays() {
+        let tokens = vec![
+            Token::Tuple(vec![Token::String("foo".to_string()), Token::Uint(u64::MAX.into())]),
+            Token::FixedArray(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+        ];
+        let outputs = vec![names("obj", &["name", "amount"]), names("", &[])];
+
+        assert_eq!(
+            format_named_tokens(&tokens, &outputs),
+            r#"{"1":["1","2"],"obj":{"amount":"18446744073709551615","name":"foo"}}"#
+        );
+        assert_eq!(format_tokens(&tokens), r#"[["foo",18446744073709551615],[1,2]]"#);
+    }
+
+    #[test]
+    fn format_named_single_unnamed_output() {
+        let tokens = vec![Token::Array(vec![Token::Tuple(vec![Token::Bool(true)])])];
+        assert_eq!(format_named_tokens(&tokens, &[names("", &["paid"])]), r#"[{"paid":true}]"#);
+        assert_eq!(format_named_tokens(&[], &[]), "null");
+    }
+}
@@ -8,4 +8,24 @@ pub mod pack;
 pub use pack::*;
 pub mod address;
 pub use address::*;
+pub mod revert;
+pub use revert::*;
+pub mod format;
+pub use format::*;
+pub mod typed_data;
+pub use typed_data::*;
+pub mod signature;
+pub use signature::*;
+pub mod fees;
+pub use fees::*;
+pub mod overrides;
+pub use overrides::*;
+pub mod trace;
+pub use trace::*;
+pub mod block;
+pub use block::*;
+pub mod rpc_error;
+pub use rpc_error::*;
+pub mod retry;
+pub use retry::*;
 mod regex;
\ No newline at end of file
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use ethers_core::types::{Address, Bytes, H256, U256, U64};
+use serde::Serialize;
+
+use crate::error::EncodeError;
+
+/// State overrides of a call, by account address. Sent as the third `eth_call` parameter
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+/// Overrides of an account's state for the duration of a call
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Replaces the whole storage of the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    /// Replaces the given storage slots, keeping the other ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Overrides of the block a call is executed in. Sent as the fourth `eth_call` parameter,
+/// which only some nodes (e.g. geth) support
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random: Option<H256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<U256>,
+}
+
+/// Collects account overrides into a state override, rejecting accounts overridden twice
+/// or overriding both their whole storage and some of its slots
+pub fn state_override<I>(accounts: I) -> Result<StateOverride, EncodeError>
+where
+    I: IntoIterator<Item = (Address, AccountOverride)>,
+{
+    let mut state_override = StateOverride::new();
+    for (address, account) in accounts {
+        if account.state.is_some() && account.state_diff.is_some() {
+            return Err(EncodeError::InvalidInput(format!(
+                "State override of {:?} can't set both state and stateDiff",
+                address
+            )));
+        }
+        if state_override.insert(address, account).is_some() {
+            return Err(EncodeError::InvalidInput(format!(
+                "State of {:?} is overridden more than once",
+                address
+            )));
+        }
+    }
+    Ok(state_override)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{state_override, AccountOverride, BlockOverrides};
+    use ethers_core::types::{Address, H256, U256};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn serialize_state_override() {
+        let address = Address::from_low_u64_be(1);
+        let slot = H256::from_low_u64_be(2);
+        let account = AccountOverride {
+            balance: Some(U256::from(1000)),
+            code: Some(vec![0x60, 0x00].into()),
+            state_diff: Some(BTreeMap::from([(slot, H256::from_low_u64_be(3))])),
+            ..Default::default()
+        };
+        let state = state_override([(address, account)]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&state).unwrap(),
+            json!({
+                "0x0000000000000000000000000000000000000001": {
+                    "balance": "0x3e8",
+                    "code": "0x6000",
+                    "stateDiff": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000002":
+                            "0x0000000000000000000000000000000000000000000000000000000000000003"
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_block_overrides() {
+        let overrides = BlockOverrides {
+            time: Some(1_700_000_000u64.into()),
+            base_fee: Some(U256::zero()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&overrides).unwrap(),
+            json!({ "time": "0x6553f100", "baseFee": "0x0" })
+        );
+    }
+
+    #[test]
+    fn invalid_state_override() {
+        let address = Address::from_low_u64_be(1);
+        let both = AccountOverride {
+            state: Some(BTreeMap::new()),
+            state_diff: Some(BTreeMap::new()),
+            ..Default::default()
+        };
+        assert!(state_override([(address, both)]).is_err());
+
+        let twice = [(address, AccountOverride::default()), (address, AccountOverride::default())];
+        assert!(state_override(twice).is_err());
+    }
+}
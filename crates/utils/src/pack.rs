@@ -129,17 +129,19 @@ fn _pack(type_: &str, value: &str, is_array: Option<bool>) -> Result<Vec<u8>, St
         let signed: bool = cap.0 == "int";
 
         let has_size = cap.1.len() > 0;
-        let size: i32 = if has_size { cap.1.parse::<i32>().unwrap() } else { 256 };
+        let size: i32 = match has_size {
+            true => cap.1.parse::<i32>().map_err(|_| format!("invalid number type {}", type_))?,
+            false => 256,
+        };
 
         if !(!has_size || size.to_string() == cap.1 && size % 8 == 0 && size != 0 && size <= 256) {
             return Err(format!("invalid number type {}", type_));
         }
 
-        let value = if signed {
-            to_twos(&value.parse::<BigInt>().unwrap(), size as usize)?
-        } else {
-            value.parse::<BigInt>().unwrap()
-        };
+        let number = value
+            .parse::<BigInt>()
+            .map_err(|_| format!("invalid value {} for type {}", value, type_))?;
+        let value = if signed { to_twos(&number, size as usize)? } else { number };
 
         let be = to_be_array(&value)?;
         let padded = zero_pad_value(&hexlify(&be), (size / 8) as usize)?;
@@ -147,7 +149,7 @@ fn _pack(type_: &str, value: &str, is_array: Option<bool>) -> Result<Vec<u8>, St
     }
 
     if let Some(cap) = matches_regex_bytes(type_) {
-        let size = if cap.len() > 0 { cap.parse::<usize>().unwrap() } else { 0 };
+        let size = cap.parse::<usize>().unwrap_or(0);
 
         if size == 0 || size > 32 {
             return Err(format!("invalid bytes type {}", type_));
@@ -168,7 +170,11 @@ fn _pack(type_: &str, value: &str, is_array: Option<bool>) -> Result<Vec<u8>, St
 
     if let Some(cap) = matches_regex_array(type_) {
         let base_type = cap.0;
-        let count: usize = if cap.1.len() > 0 { cap.1.parse::<usize>().unwrap() } else { value.len() };
+        let count: usize = match !cap.1.is_empty() {
+            true => cap.1.parse::<usize>()
+                .map_err(|_| format!("invalid array type {}", type_))?,
+            false => value.len(),
+        };
 
         if count != value.len() {
             return Err(format!("invalid array length {} for type {}", value, type_));
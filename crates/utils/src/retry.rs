@@ -0,0 +1,159 @@
+use crate::rpc_error::{classify_rpc_error, RpcErrorReason};
+
+/// Class of transient errors which a request can be retried on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryableError {
+    /// Request refused by a node limiting the rate of requests
+    RateLimited,
+    /// Request which timed out
+    Timeout,
+    /// Block not known yet by the node serving the request, just after it was mined
+    HeaderNotFound,
+    /// Error without a JSON-RPC error response, like a connection failure
+    Transport,
+}
+
+/// Retries of requests failing with transient errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts of a request, including the first one
+    pub max_attempts: u32,
+    /// Classes of errors retried
+    pub retry_on: Vec<RetryableError>,
+}
+
+impl Default for RetryPolicy {
+    /// Requests are not retried by default
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::preset()
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy whose fields are used for the ones a configured policy doesn't set
+    pub fn preset() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_on: [
+                RetryableError::RateLimited,
+                RetryableError::Timeout,
+                RetryableError::HeaderNotFound,
+            ]
+            .to_vec(),
+        }
+    }
+
+    /// Whether a request of `method` which failed with `error` at its `attempt`th attempt
+    /// (starting at 1) is retried. Only idempotent methods are retried, except for raw
+    /// transactions which are retried when the node refused them without processing them
+    pub fn should_retry(&self, method: &str, error: Option<RetryableError>, attempt: u32) -> bool {
+        let error = match error {
+            Some(error) if attempt < self.max_attempts && self.retry_on.contains(&error) => error,
+            _ => return false,
+        };
+        match method {
+            "eth_sendRawTransaction" => error == RetryableError::RateLimited,
+            method => is_idempotent(method),
+        }
+    }
+}
+
+/// Whether a JSON-RPC method only reads the chain, so sending it twice has no effect
+pub fn is_idempotent(method: &str) -> bool {
+    matches!(
+        method,
+        "eth_chainId"
+            | "net_version"
+            | "eth_blockNumber"
+            | "eth_getBalance"
+            | "eth_getCode"
+            | "eth_getStorageAt"
+            | "eth_getTransactionCount"
+            | "eth_getTransactionByHash"
+            | "eth_getTransactionReceipt"
+            | "eth_getBlockByNumber"
+            | "eth_getBlockByHash"
+            | "eth_getLogs"
+            | "eth_gasPrice"
+            | "eth_maxPriorityFeePerGas"
+            | "eth_feeHistory"
+            | "eth_call"
+            | "eth_estimateGas"
+            | "eth_createAccessList"
+            | "debug_traceCall"
+            | "debug_traceTransaction"
+    )
+}
+
+/// Classifies an error as a transient one, by the code of its JSON-RPC error response if
+/// it has one, and its message
+pub fn classify_retryable(code: Option<i64>, message: &str) -> Option<RetryableError> {
+    if let Some(code) = code {
+        if classify_rpc_error(code, message) == Some(RpcErrorReason::RateLimited) {
+            return Some(RetryableError::RateLimited);
+        }
+    }
+    let lowercase = message.to_lowercase();
+    if lowercase.contains("rate limit") || lowercase.contains("too many requests") {
+        return Some(RetryableError::RateLimited);
+    }
+    if lowercase.contains("timeout") || lowercase.contains("timed out") {
+        return Some(RetryableError::Timeout);
+    }
+    if lowercase.contains("header not found") || lowercase.contains("unknown block") {
+        return Some(RetryableError::HeaderNotFound);
+    }
+    match code {
+        Some(_) => None,
+        None => Some(RetryableError::Transport),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_retryable, RetryPolicy, RetryableError};
+
+    #[test]
+    fn retry_idempotent_methods() {
+        let policy = RetryPolicy::preset();
+        let rate_limited = Some(RetryableError::RateLimited);
+        let timeout = Some(RetryableError::Timeout);
+        assert!(policy.should_retry("eth_call", rate_limited, 1));
+        assert!(policy.should_retry("eth_getBalance", timeout, 2));
+        assert!(!policy.should_retry("eth_getBalance", timeout, 3));
+        assert!(!policy.should_retry("eth_getBalance", None, 1));
+        // transport errors are only retried when the policy says so
+        assert!(!policy.should_retry("eth_call", Some(RetryableError::Transport), 1));
+
+        assert!(!policy.should_retry("eth_sendTransaction", rate_limited, 1));
+        assert!(policy.should_retry("eth_sendRawTransaction", rate_limited, 1));
+        assert!(!policy.should_retry("eth_sendRawTransaction", timeout, 1));
+
+        assert!(!RetryPolicy::default().should_retry("eth_call", rate_limited, 1));
+    }
+
+    #[test]
+    fn classify_retryable_errors() {
+        assert_eq!(
+            classify_retryable(Some(429), "Too Many Requests"),
+            Some(RetryableError::RateLimited)
+        );
+        assert_eq!(
+            classify_retryable(Some(-32005), "daily request count exceeded"),
+            Some(RetryableError::RateLimited)
+        );
+        assert_eq!(
+            classify_retryable(Some(-32000), "header not found"),
+            Some(RetryableError::HeaderNotFound)
+        );
+        assert_eq!(classify_retryable(None, "timeout exceeded"), Some(RetryableError::Timeout));
+        assert_eq!(
+            classify_retryable(None, "could not detect network"),
+            Some(RetryableError::Transport)
+        );
+        assert_eq!(classify_retryable(Some(-32000), "nonce too low"), None);
+    }
+}
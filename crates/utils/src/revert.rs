@@ -0,0 +1,206 @@
+use ethers_core::abi::{decode, Abi, Param, ParamType, Token};
+use std::fmt;
+
+/// Selector of `Error(string)`, used by `require` and `revert` with a reason
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`, used by failed assertions and arithmetic checks
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertKind {
+    /// `Error(string)` revert reason
+    Error,
+    /// `Panic(uint256)` with a panic code
+    Panic,
+    /// Custom error declared in the contract ABI
+    CustomError,
+    /// Revert data that could not be decoded
+    Unknown,
+}
+
+impl fmt::Display for RevertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            RevertKind::Error => "Error",
+            RevertKind::Panic => "Panic",
+            RevertKind::CustomError => "CustomError",
+            RevertKind::Unknown => "Unknown",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedRevert {
+    pub kind: RevertKind,
+    /// Name of the error (e.g. `Error`, `Panic` or the custom error name)
+    pub name: Option<String>,
+    /// Decoded arguments of the error, with the params they were decoded with
+    pub args: Vec<(Param, Token)>,
+    /// Human readable reason of the revert
+    pub reason: Option<String>,
+}
+
+/// Decodes the data returned by a reverted call as `Error(string)`, `Panic(uint256)` or one
+/// of the custom errors declared in `abi`.
+pub fn decode_revert(data: &[u8], abi: Option<&Abi>) -> DecodedRevert {
+    let unknown = DecodedRevert {
+        kind: RevertKind::Unknown,
+        name: None,
+        args: vec![],
+        reason: None,
+    };
+    if data.len() < 4 {
+        return unknown;
+    }
+    let (selector, encoded) = data.split_at(4);
+
+    if selector == ERROR_SELECTOR {
+        return match decode(&[ParamType::String], encoded) {
+            Ok(mut tokens) => {
+                let token = tokens.remove(0);
+                DecodedRevert {
+                    kind: RevertKind::Error,
+                    name: Some("Error".to_string()),
+                    reason: token.clone().into_string(),
+                    args: vec![(param("reason", ParamType::String), token)],
+                }
+            }
+            Err(_) => unknown,
+        };
+    }
+
+    if selector == PANIC_SELECTOR {
+        return match decode(&[ParamType::Uint(256)], encoded) {
+            Ok(mut tokens) => {
+                let token = tokens.remove(0);
+                let code = token.clone().into_uint().unwrap_or_default();
+                DecodedRevert {
+                    kind: RevertKind::Panic,
+                    name: Some("Panic".to_string()),
+                    reason: Some(format!("{} (panic code {:#04x})", panic_reason(code.low_u64()), code)),
+                    args: vec![(param("code", ParamType::Uint(256)), token)],
+                }
+            }
+            Err(_) => unknown,
+        };
+    }
+
+    if let Some(abi) = abi {
+        let custom_error = abi
+            .errors()
+            .filter(|e| e.signature()[0..4] == *selector)
+            .find_map(|e| e.decode(encoded).ok().map(|tokens| (e, tokens)));
+        if let Some((error, tokens)) = custom_error {
+            let values: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+            return DecodedRevert {
+                kind: RevertKind::CustomError,
+                name: Some(error.name.clone()),
+                reason: Some(format!("{}({})", error.name, values.join(", "))),
+                args: error.inputs.iter().cloned().zip(tokens).collect(),
+            };
+        }
+    }
+
+    unknown
+}
+
+/// Returns the meaning of a Solidity panic code
+pub fn panic_reason(code: u64) -> &'static str {
+    match code {
+        0x00 => "generic compiler inserted panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid encoded storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic code",
+    }
+}
+
+/// Looks for revert data in the text of a JSON-RPC error. Nodes embed it in different
+/// places, so the first hex value shaped like a selector followed by ABI words is used.
+pub fn find_revert_data(text: &str) -> Option<Vec<u8>> {
+    text.match_indices("0x").find_map(|(start, _)| {
+        let hex: String = text[start + 2..]
+            .chars()
+            .take_while(|c| c.is_ascii_hexdigit())
+            .collect();
+        // a 4 bytes selector followed by 32 bytes words
+        if hex.len() % 64 != 8 {
+            return None;
+        }
+        hex::decode(hex).ok()
+    })
+}
+
+fn param(name: &str, kind: ParamType) -> Param {
+    Param {
+        name: name.to_string(),
+        kind,
+        internal_type: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_revert, find_revert_data, RevertKind};
+    use ethers_core::abi::{encode, Abi, Token};
+
+    #[test]
+    fn decode_error_string() {
+        let mut data = hex::decode("08c379a0").unwrap();
+        data.extend(encode(&[Token::String("Not enough funds".to_string())]));
+
+        let revert = decode_revert(&data, None);
+        assert_eq!(revert.kind, RevertKind::Error);
+        assert_eq!(revert.reason, Some("Not enough funds".to_string()));
+    }
+
+    #[test]
+    fn decode_panic() {
+        let mut data = hex::decode("4e487b71").unwrap();
+        data.extend(encode(&[Token::Uint(0x11.into())]));
+
+        let revert = decode_revert(&data, None);
+        assert_eq!(revert.kind, RevertKind::Panic);
+        assert_eq!(revert.args[0].1, Token::Uint(0x11.into()));
+        assert_eq!(
+            revert.reason,
+            Some("arithmetic overflow or underflow (panic code 0x11)".to_string())
+        );
+    }
+
+    #[test]
+    fn decode_custom_error() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{"inputs":[{"name":"available","type":"uint256"},{"name":"required","type":"uint256"}],"name":"InsufficientBalance","type":"error"}]"#,
+        )
+        .unwrap();
+        let error = abi.errors().next().unwrap();
+        let data = error
+            .encode(&[Token::Uint(1.into()), Token::Uint(2.into())])
+            .unwrap();
+
+        let revert = decode_revert(&data, Some(&abi));
+        assert_eq!(revert.kind, RevertKind::CustomError);
+        assert_eq!(revert.name, Some("InsufficientBalance".to_string()));
+        assert_eq!(revert.args[1].0.name, "required");
+        assert_eq!(revert.args[1].1, Token::Uint(2.into()));
+
+        assert_eq!(decode_revert(&data, None).kind, RevertKind::Unknown);
+    }
+
+    #[test]
+    fn find_revert_data_in_error_text() {
+        let text = r#"processing response error (body="{\"error\":{\"code\":-32000,\"message\":\"execution reverted\",\"data\":\"0x4e487b710000000000000000000000000000000000000000000000000000000000000011\"}}", from="0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1")"#;
+        let data = find_revert_data(text).unwrap();
+        assert_eq!(decode_revert(&data, None).kind, RevertKind::Panic);
+
+        assert!(find_revert_data("VM Exception while processing transaction: out of gas").is_none());
+    }
+}
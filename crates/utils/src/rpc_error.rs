@@ -0,0 +1,225 @@
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::revert::find_revert_data;
+
+/// Error object of a JSON-RPC response
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RpcErrorPayload {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+/// Cause of a JSON-RPC error, told apart by its code and message like ethers.js does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorReason {
+    /// Call or gas estimate of a transaction which reverts
+    Reverted,
+    /// Transaction with a nonce already used by the sender
+    NonceTooLow,
+    /// Sender without enough balance for the value and gas of the transaction
+    InsufficientFunds,
+    /// Replacement of a pending transaction without high enough fees
+    ReplacementUnderpriced,
+    /// Request refused by a node limiting the rate of requests
+    RateLimited,
+}
+
+impl fmt::Display for RpcErrorReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            RpcErrorReason::Reverted => "Reverted",
+            RpcErrorReason::NonceTooLow => "NonceTooLow",
+            RpcErrorReason::InsufficientFunds => "InsufficientFunds",
+            RpcErrorReason::ReplacementUnderpriced => "ReplacementUnderpriced",
+            RpcErrorReason::RateLimited => "RateLimited",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl RpcErrorPayload {
+    pub fn reason(&self) -> Option<RpcErrorReason> {
+        classify_rpc_error(self.code, &self.message)
+    }
+
+    /// Revert data of a reverted call, which nodes put in different places of the error data
+    pub fn revert_data(&self) -> Option<Vec<u8>> {
+        if self.reason() != Some(RpcErrorReason::Reverted) {
+            return None;
+        }
+        self.data.as_ref().and_then(spelunk_revert_data)
+    }
+}
+
+/// Classifies a JSON-RPC error by its code and message
+pub fn classify_rpc_error(code: i64, message: &str) -> Option<RpcErrorReason> {
+    let message = message.to_lowercase();
+    // -32005 is the "limit exceeded" code of EIP-1474
+    if code == 429
+        || code == -32005
+        || message.contains("rate limit")
+        || message.contains("too many requests")
+    {
+        return Some(RpcErrorReason::RateLimited);
+    }
+    // geth reports "execution reverted" while ganache reports "revert"
+    if code == 3 || message.contains("revert") {
+        return Some(RpcErrorReason::Reverted);
+    }
+    // ganache queues transactions with a nonce too high, so its nonce errors are too low ones
+    if message.contains("nonce too low")
+        || message.contains("nonce is too low")
+        || message.contains("doesn't have the correct nonce")
+    {
+        return Some(RpcErrorReason::NonceTooLow);
+    }
+    if message.contains("insufficient funds") || message.contains("doesn't have enough funds") {
+        return Some(RpcErrorReason::InsufficientFunds);
+    }
+    if message.contains("underpriced")
+        || (message.contains("gas price") && message.contains("too low"))
+    {
+        return Some(RpcErrorReason::ReplacementUnderpriced);
+    }
+    None
+}
+
+/// Parses the JSON-RPC error embedded in the text of an error, like the errors of the
+/// provider plugin. ethers.js embeds it as JSON in its messages, sometimes escaped in the
+/// body of the response.
+pub fn parse_rpc_error(text: &str) -> Option<RpcErrorPayload> {
+    find_rpc_error(text).or_else(|| find_rpc_error(&text.replace("\\\"", "\"")))
+}
+
+fn find_rpc_error(text: &str) -> Option<RpcErrorPayload> {
+    text.match_indices('{').find_map(|(start, _)| {
+        // only the JSON value starting at the brace is parsed, ignoring the text after it
+        let value: Value = serde_json::Deserializer::from_str(&text[start..])
+            .into_iter()
+            .next()?
+            .ok()?;
+        spelunk_rpc_error(&value)
+    })
+}
+
+// looks for an object with a code and a message, preferring the error of a response
+fn spelunk_rpc_error(value: &Value) -> Option<RpcErrorPayload> {
+    match value {
+        Value::Object(object) => {
+            if let Some(error) = object.get("error").and_then(spelunk_rpc_error) {
+                return Some(error);
+            }
+            let is_error = matches!(object.get("code"), Some(code) if code.is_i64())
+                && matches!(object.get("message"), Some(message) if message.is_string());
+            if is_error {
+                return serde_json::from_value(value.clone()).ok();
+            }
+            object.values().find_map(spelunk_rpc_error)
+        }
+        Value::Array(values) => values.iter().find_map(spelunk_rpc_error),
+        _ => None,
+    }
+}
+
+fn spelunk_revert_data(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::String(text) => find_revert_data(text),
+        Value::Object(object) => object.values().find_map(spelunk_revert_data),
+        Value::Array(values) => values.iter().find_map(spelunk_revert_data),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_rpc_error, parse_rpc_error, RpcErrorReason};
+    use serde_json::json;
+
+    const REVERT_DATA: &str = "0x08c379a0\
+        0000000000000000000000000000000000000000000000000000000000000020\
+        0000000000000000000000000000000000000000000000000000000000000004\
+        6e6f706500000000000000000000000000000000000000000000000000000000";
+
+    #[test]
+    fn parse_embedded_rpc_error() {
+        let text = format!(
+            "cannot estimate gas; transaction may fail (reason=\"execution reverted: nope\", \
+             error={{\"code\":3,\"message\":\"execution reverted: nope\",\"data\":\"{}\"}}, \
+             code=UNPREDICTABLE_GAS_LIMIT, version=providers/5.7.2)",
+            REVERT_DATA
+        );
+        let error = parse_rpc_error(&text).unwrap();
+        assert_eq!(error.code, 3);
+        assert_eq!(error.message, "execution reverted: nope");
+        assert_eq!(error.data, Some(json!(REVERT_DATA)));
+        assert_eq!(error.reason(), Some(RpcErrorReason::Reverted));
+        assert_eq!(error.revert_data(), Some(hex::decode(&REVERT_DATA[2..]).unwrap()));
+    }
+
+    #[test]
+    fn parse_escaped_response_body() {
+        let text = "processing response error (body=\"{\\\"jsonrpc\\\":\\\"2.0\\\",\\\"id\\\":42,\
+            \\\"error\\\":{\\\"code\\\":-32000,\\\"message\\\":\\\"nonce too low\\\"}}\", \
+            error={\"code\":-32000}, requestMethod=\"POST\", code=SERVER_ERROR)";
+        let error = parse_rpc_error(text).unwrap();
+        assert_eq!(error.code, -32000);
+        assert_eq!(error.message, "nonce too low");
+        assert_eq!(error.data, None);
+        assert_eq!(error.reason(), Some(RpcErrorReason::NonceTooLow));
+        assert_eq!(error.revert_data(), None);
+    }
+
+    #[test]
+    fn parse_nested_revert_data() {
+        // ganache puts the revert data under the hash of the transaction
+        let text = json!({
+            "code": -32000,
+            "message": "VM Exception while processing transaction: revert nope",
+            "data": {
+                "0x5c3a6f5a8e1f0b1c7d2e9a4b3c6d8e0f1a2b3c4d5e6f708192a3b4c5d6e7f809": {
+                    "error": "revert",
+                    "program_counter": 130,
+                    "return": REVERT_DATA
+                },
+                "name": "RuntimeError"
+            }
+        })
+        .to_string();
+        let error = parse_rpc_error(&text).unwrap();
+        assert_eq!(error.revert_data(), Some(hex::decode(&REVERT_DATA[2..]).unwrap()));
+    }
+
+    #[test]
+    fn text_without_rpc_error() {
+        assert!(parse_rpc_error("could not detect network").is_none());
+        assert!(parse_rpc_error("error={\"code\":-32000}").is_none());
+        assert!(parse_rpc_error("{not json").is_none());
+    }
+
+    #[test]
+    fn classify_rpc_errors() {
+        assert_eq!(classify_rpc_error(429, ""), Some(RpcErrorReason::RateLimited));
+        assert_eq!(
+            classify_rpc_error(-32005, "limit exceeded"),
+            Some(RpcErrorReason::RateLimited)
+        );
+        assert_eq!(
+            classify_rpc_error(-32000, "insufficient funds for gas * price + value"),
+            Some(RpcErrorReason::InsufficientFunds)
+        );
+        assert_eq!(
+            classify_rpc_error(-32000, "sender doesn't have enough funds to send tx"),
+            Some(RpcErrorReason::InsufficientFunds)
+        );
+        assert_eq!(
+            classify_rpc_error(-32000, "replacement transaction underpriced"),
+            Some(RpcErrorReason::ReplacementUnderpriced)
+        );
+        assert_eq!(classify_rpc_error(-32601, "method not found"), None);
+    }
+}
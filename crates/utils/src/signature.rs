@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip712::TypedData},
+    Address, RecoveryMessage, Signature, H256,
+};
+use ethers_core::utils::rlp::Rlp;
+
+use crate::error::EncodeError;
+
+/// Parses a 65 bytes signature, as a hex string with or without `0x` prefix
+pub fn parse_signature(signature: &str) -> Result<Signature, EncodeError> {
+    Signature::from_str(signature).map_err(|e| EncodeError::SignatureError(e.to_string()))
+}
+
+/// Recovers the address that signed the given digest
+pub fn recover_address(digest: H256, signature: &Signature) -> Result<Address, EncodeError> {
+    recover(RecoveryMessage::Hash(digest), signature)
+}
+
+/// Recovers the address that signed a message with the EIP-191 prefix, like `signMessage`
+pub fn verify_message<M: AsRef<[u8]>>(
+    message: M,
+    signature: &Signature,
+) -> Result<Address, EncodeError> {
+    recover(RecoveryMessage::Data(message.as_ref().to_vec()), signature)
+}
+
+/// Recovers the address that signed EIP-712 typed data, like `signTypedData`
+pub fn verify_typed_data(
+    typed_data: &TypedData,
+    signature: &Signature,
+) -> Result<Address, EncodeError> {
+    signature
+        .recover_typed_data(typed_data.clone())
+        .map_err(|e| EncodeError::SignatureError(e.to_string()))
+}
+
+/// Recovers the sender of a signed raw transaction, legacy or EIP-2718 typed
+pub fn recover_transaction_signer(raw: &[u8]) -> Result<Address, EncodeError> {
+    let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(raw))
+        .map_err(|e| EncodeError::SignatureError(e.to_string()))?;
+    recover_address(tx.sighash(), &signature)
+}
+
+fn recover(message: RecoveryMessage, signature: &Signature) -> Result<Address, EncodeError> {
+    signature
+        .recover(message)
+        .map_err(|e| EncodeError::SignatureError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_signature, recover_transaction_signer, verify_message, verify_typed_data};
+    use crate::parse_typed_data;
+    use ethers_core::types::Address;
+    use serde_json::json;
+
+    // address of the private key 0x4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d
+    const SIGNER: &str = "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1";
+
+    fn signer() -> Address {
+        SIGNER.parse().unwrap()
+    }
+
+    #[test]
+    fn verify_signed_message() {
+        let signature = parse_signature("0xa4708243bf782c6769ed04d83e7192dbcf4fc131aa54fde9d889d8633ae39dab03d7babd2392982dff6bc20177f7d887e27e50848c851320ee89c6c63d18ca761c").unwrap();
+        assert_eq!(verify_message("Hello World", &signature).unwrap(), signer());
+        assert_ne!(verify_message("Hello Bob", &signature).unwrap(), signer());
+    }
+
+    #[test]
+    fn verify_signed_typed_data() {
+        let typed_data = parse_typed_data(&json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+                "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap();
+        let signature = parse_signature("0x12bdd486cb42c3b3c414bb04253acfe7d402559e7637562987af6bd78508f38623c1cc09880613762cc913d49fd7d3c091be974c0dee83fb233300b6b58727311c").unwrap();
+        assert_eq!(verify_typed_data(&typed_data, &signature).unwrap(), signer());
+    }
+
+    #[test]
+    fn recover_eip155_transaction_signer() {
+        // example of the EIP-155 specification, signed with the private key 0x4646...4646
+        let raw = hex::decode("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83").unwrap();
+        let sender: Address = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f".parse().unwrap();
+        assert_eq!(recover_transaction_signer(&raw).unwrap(), sender);
+
+        assert!(recover_transaction_signer(&raw[..10]).is_err());
+    }
+
+    #[test]
+    fn invalid_signature() {
+        assert!(parse_signature("0x1234").is_err());
+    }
+}
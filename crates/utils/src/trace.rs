@@ -0,0 +1,183 @@
+use ethers_core::{
+    abi::{Abi, Function, Token},
+    types::{Address, Bytes, U256},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Frame of the `callTracer` of `debug_traceCall` and `debug_traceTransaction`: a call and
+/// the internal calls it made
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// Kind of call (e.g. `CALL`, `STATICCALL`, `DELEGATECALL`, `CREATE`)
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub from: Address,
+    #[serde(default)]
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub value: Option<U256>,
+    #[serde(default)]
+    pub gas: U256,
+    #[serde(default)]
+    pub gas_used: U256,
+    #[serde(default)]
+    pub input: Bytes,
+    #[serde(default)]
+    pub output: Option<Bytes>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub revert_reason: Option<String>,
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+/// Options of the `callTracer`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallTracerOptions {
+    /// Trace only the top call, without its internal calls
+    pub only_top_call: bool,
+    /// Timeout of the tracing (e.g. `10s`), instead of the node's default
+    pub timeout: Option<String>,
+}
+
+impl CallTracerOptions {
+    /// Tracing options of the debug API selecting the `callTracer`
+    pub fn to_value(&self) -> Value {
+        let mut options = json!({
+            "tracer": "callTracer",
+            "tracerConfig": { "onlyTopCall": self.only_top_call },
+        });
+        if let Some(timeout) = &self.timeout {
+            options["timeout"] = json!(timeout);
+        }
+        options
+    }
+}
+
+/// Call decoded with a function of a contract ABI
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    pub function: Function,
+    pub args: Vec<Token>,
+    /// Values returned by the call, if it has an output that could be decoded
+    pub result: Option<Vec<Token>>,
+}
+
+/// Decodes the input of a call with the function of `abi` matching its selector, and its
+/// output if given. Returns `None` if no function of the ABI matches the input.
+pub fn decode_call(abi: &Abi, input: &[u8], output: Option<&[u8]>) -> Option<DecodedCall> {
+    if input.len() < 4 {
+        return None;
+    }
+    let (selector, encoded) = input.split_at(4);
+
+    // overloaded functions with the same selector are told apart by their input encoding
+    abi.functions()
+        .filter(|function| function.short_signature() == selector)
+        .find_map(|function| {
+            let args = function.decode_input(encoded).ok()?;
+            let result = output.and_then(|output| function.decode_output(output).ok());
+            Some(DecodedCall {
+                function: function.clone(),
+                args,
+                result,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_call, CallFrame, CallTracerOptions};
+    use crate::encode_function;
+    use ethers_core::abi::{Abi, Token};
+    use ethers_core::types::{Address, U256};
+    use serde_json::json;
+
+    const ERC20_ABI: &str = r#"[
+        {"type":"function","name":"transfer","stateMutability":"nonpayable",
+         "inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],
+         "outputs":[{"name":"","type":"bool"}]}
+    ]"#;
+
+    #[test]
+    fn deserialize_call_tracer_frames() {
+        let frame: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1",
+            "to": "0x5b1869d9a4c187f2eaa108f3062412ecf0526b24",
+            "value": "0x0",
+            "gas": "0x1d4c0",
+            "gasUsed": "0x6d60",
+            "input": "0x",
+            "error": "execution reverted",
+            "calls": [{
+                "type": "STATICCALL",
+                "from": "0x5b1869d9a4c187f2eaa108f3062412ecf0526b24",
+                "to": "0xcfeb869f69431e42cdb54a4f4f105c19c080a601",
+                "gas": "0x1c2b8",
+                "gasUsed": "0x2b1",
+                "input": "0x6d4ce63c",
+                "output": "0x000000000000000000000000000000000000000000000000000000000000002a"
+            }]
+        }))
+        .unwrap();
+        assert_eq!(frame.call_type, "CALL");
+        assert_eq!(frame.gas, U256::from(120000));
+        assert_eq!(frame.error.as_deref(), Some("execution reverted"));
+        assert_eq!(frame.calls.len(), 1);
+
+        let inner = &frame.calls[0];
+        assert_eq!(inner.call_type, "STATICCALL");
+        assert_eq!(inner.value, None);
+        assert_eq!(inner.output.as_ref().unwrap().len(), 32);
+        assert!(inner.calls.is_empty());
+    }
+
+    #[test]
+    fn decode_call_with_abi() {
+        let abi: Abi = serde_json::from_str(ERC20_ABI).unwrap();
+        let to = "0xcfeb869f69431e42cdb54a4f4f105c19c080a601";
+        let (_, input) = encode_function(
+            "function transfer(address to, uint256 amount)",
+            &[to.to_string(), "1000".to_string()].to_vec(),
+        )
+        .unwrap();
+        let mut output = [0u8; 32];
+        output[31] = 1;
+
+        let decoded = decode_call(&abi, &input, Some(&output)).unwrap();
+        assert_eq!(decoded.function.name, "transfer");
+        let to: Address = to.parse().unwrap();
+        assert_eq!(
+            decoded.args,
+            [Token::Address(to), Token::Uint(U256::from(1000))].to_vec()
+        );
+        assert_eq!(decoded.result, Some([Token::Bool(true)].to_vec()));
+
+        assert!(decode_call(&abi, &[0x6d, 0x4c, 0xe6, 0x3c], None).is_none());
+        assert!(decode_call(&abi, &input[..2], None).is_none());
+    }
+
+    #[test]
+    fn call_tracer_options() {
+        assert_eq!(
+            CallTracerOptions::default().to_value(),
+            json!({ "tracer": "callTracer", "tracerConfig": { "onlyTopCall": false } })
+        );
+        let options = CallTracerOptions {
+            only_top_call: true,
+            timeout: Some("10s".to_string()),
+        };
+        assert_eq!(
+            options.to_value(),
+            json!({
+                "tracer": "callTracer",
+                "tracerConfig": { "onlyTopCall": true },
+                "timeout": "10s"
+            })
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use ethers_core::types::{
+    transaction::eip712::{Eip712, TypedData},
+    H256,
+};
+use serde_json::Value;
+
+use crate::error::EncodeError;
+
+/// Hashes of an EIP-712 typed data payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedDataHash {
+    /// Hash of the `EIP712Domain` struct
+    pub domain_separator: H256,
+    /// Hash of the message, `None` when the primary type is `EIP712Domain`
+    pub struct_hash: Option<H256>,
+    /// Digest to sign, `keccak256("\x19\x01" ‖ domainSeparator ‖ structHash)`
+    pub digest: H256,
+}
+
+/// Parses an `eth_signTypedData_v4` payload, given as a JSON object or as its JSON string
+pub fn parse_typed_data(payload: &Value) -> Result<TypedData, EncodeError> {
+    serde_json::from_value(payload.clone())
+        .map_err(|e| EncodeError::TypedDataError(e.to_string()))
+}
+
+pub fn hash_typed_data(typed_data: &TypedData) -> Result<TypedDataHash, EncodeError> {
+    let domain_separator = H256::from(typed_data.domain.separator());
+    let struct_hash = match typed_data.primary_type.as_str() {
+        "EIP712Domain" => None,
+        _ => Some(H256::from(
+            typed_data
+                .struct_hash()
+                .map_err(|e| EncodeError::TypedDataError(e.to_string()))?,
+        )),
+    };
+    let digest = typed_data
+        .encode_eip712()
+        .map_err(|e| EncodeError::TypedDataError(e.to_string()))?;
+    Ok(TypedDataHash {
+        domain_separator,
+        struct_hash,
+        digest: H256::from(digest),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_typed_data, parse_typed_data};
+    use serde_json::json;
+
+    // example of the EIP-712 specification
+    fn mail() -> serde_json::Value {
+        json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+                "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+                "contents": "Hello, Bob!"
+            }
+        })
+    }
+
+    #[test]
+    fn hash_mail() {
+        let typed_data = parse_typed_data(&mail()).unwrap();
+        let hash = hash_typed_data(&typed_data).unwrap();
+        assert_eq!(
+            format!("{:?}", hash.domain_separator),
+            "0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            format!("{:?}", hash.struct_hash.unwrap()),
+            "0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            format!("{:?}", hash.digest),
+            "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn parse_json_string() {
+        let payload = serde_json::Value::String(mail().to_string());
+        let typed_data = parse_typed_data(&payload).unwrap();
+        assert_eq!(typed_data.primary_type, "Mail");
+
+        assert!(parse_typed_data(&json!({ "message": {} })).is_err());
+    }
+}
@@ -1,2 +1,4 @@
+mod multicall;
+pub use multicall::*;
 mod transaction;
 pub use transaction::*;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use crate::{
+    error::WrapperError,
+    polywrap_provider::provider::{Provider, WrapProvider},
+};
+use ethers_core::{
+    abi::{Function, Token},
+    types::{transaction::eip2718::TypedTransaction, Address, Bytes, TransactionRequest, H160},
+};
+
+/// Multicall3 address, deployed at the same address on most chains
+pub const MULTICALL3_ADDRESS: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
+const AGGREGATE3: &str =
+    "function aggregate3((address,bool,bytes)[] calls) payable returns ((bool,bytes)[])";
+
+/// A contract view call, with its calldata already encoded
+pub struct ViewCall {
+    pub address: Address,
+    pub function: Function,
+    pub data: Bytes,
+    /// If the call fails, return its error instead of failing the whole batch
+    pub allow_failure: bool,
+}
+
+/// Executes view calls in a single `eth_call` through Multicall3's `aggregate3`, or one by
+/// one if Multicall3 isn't deployed on the chain. Each result holds the decoded output of
+/// its call, or the error of a call that was allowed to fail.
+pub fn call_contract_views(
+    provider: &WrapProvider,
+    multicall: Option<Address>,
+    calls: &[ViewCall],
+) -> Result<Vec<Result<Vec<Token>, String>>, WrapperError> {
+    if calls.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let multicall = multicall.unwrap_or(MULTICALL3_ADDRESS);
+    let aggregate3 = ethers_utils::parse_method(AGGREGATE3)?;
+    let tokens: Vec<Token> = calls
+        .iter()
+        .map(|call| {
+            Token::Tuple(vec![
+                Token::Address(call.address),
+                Token::Bool(call.allow_failure),
+                Token::Bytes(call.data.to_vec()),
+            ])
+        })
+        .collect();
+    let data: Bytes = aggregate3
+        .encode_input(&[Token::Array(tokens)])
+        .map_err(ethers_utils::EncodeError::from)?
+        .into();
+
+    // calls to an address without code return no data, so chains without Multicall3 are
+    // found without requesting its code first
+    let bytes = provider.call(&view_transaction(multicall, data), None)?;
+    if bytes.is_empty() {
+        return call_sequentially(provider, calls);
+    }
+    let results = aggregate3
+        .decode_output(&bytes)
+        .map_err(ethers_utils::EncodeError::from)?
+        .pop()
+        .and_then(Token::into_array)
+        .unwrap_or_default();
+    if results.len() != calls.len() {
+        return Err(WrapperError::ContractError(format!(
+            "Multicall returned {} results for {} calls",
+            results.len(),
+            calls.len()
+        )));
+    }
+
+    calls
+        .iter()
+        .zip(results)
+        .map(|(call, result)| {
+            let (success, return_data) = match result.into_tuple().as_deref() {
+                Some([Token::Bool(success), Token::Bytes(data)]) => (*success, data.clone()),
+                _ => {
+                    let error = "Invalid multicall result".to_string();
+                    return Err(WrapperError::ContractError(error));
+                }
+            };
+            decode_result(call, success, &return_data)
+        })
+        .collect()
+}
+
+fn call_sequentially(
+    provider: &WrapProvider,
+    calls: &[ViewCall],
+) -> Result<Vec<Result<Vec<Token>, String>>, WrapperError> {
+    calls
+        .iter()
+        .map(|call| {
+            let result = provider.call(&view_transaction(call.address, call.data.clone()), None);
+            match result {
+                Ok(bytes) => decode_result(call, true, &bytes),
+                Err(e) if call.allow_failure => Ok(Err(e.to_string())),
+                Err(e) => Err(e.into()),
+            }
+        })
+        .collect()
+}
+
+fn view_transaction(address: Address, data: Bytes) -> TypedTransaction {
+    TransactionRequest {
+        to: Some(address.into()),
+        data: Some(data),
+        ..Default::default()
+    }
+    .into()
+}
+
+// decodes the output of a call, failing the batch on errors of calls not allowed to fail
+fn decode_result(
+    call: &ViewCall,
+    success: bool,
+    data: &[u8],
+) -> Result<Result<Vec<Token>, String>, WrapperError> {
+    let error = if success {
+        match call.function.decode_output(data) {
+            Ok(tokens) => return Ok(Ok(tokens)),
+            Err(e) => WrapperError::ContractError(format!(
+                "Failed to decode result of {}: {}",
+                call.function.name, e
+            )),
+        }
+    } else {
+        let reason = ethers_utils::decode_revert(data, None)
+            .reason
+            .unwrap_or_else(|| format!("0x{}", hex::encode(data)));
+        WrapperError::Reverted(format!("Call to {} reverted: {}", call.function.name, reason))
+    };
+
+    match call.allow_failure {
+        true => Ok(Err(error.message())),
+        false => Err(error),
+    }
+}
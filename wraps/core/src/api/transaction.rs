@@ -1,8 +1,9 @@
 use crate::{
     error::WrapperError,
     polywrap_provider::{
+        nonce_manager::NonceManager,
         provider::{Provider, WrapProvider},
-        signer::WrapSigner,
+        signer::{TransactionSender, WrapSigner},
     },
 };
 use ethers_core::types::{BlockId, Chain};
@@ -10,13 +11,12 @@ use ethers_core::{
     abi::{Abi, Function, Token},
     types::{
         transaction::eip2718::TypedTransaction, Address, Bytes, Eip1559TransactionRequest,
-        TransactionRequest, H256, U256,
+        Transaction, TransactionRequest, H256, U256,
     },
-    utils::serialize,
 };
 use ethers_providers::ProviderError;
 
-use crate::mapping::EthersTxOptions;
+use crate::mapping::{CallOverrides, EthersTxOptions};
 
 pub fn create_transaction(
     address: Option<Address>,
@@ -24,7 +24,7 @@ pub fn create_transaction(
     options: &EthersTxOptions,
 ) -> TypedTransaction {
     if options.gas_price.is_some() {
-        return TransactionRequest {
+        let tx = TransactionRequest {
             to: address.map(Into::into),
             data: Some(data),
             gas: options.gas_limit,
@@ -32,8 +32,12 @@ pub fn create_transaction(
             value: options.value,
             nonce: options.nonce,
             ..Default::default()
-        }
-        .into();
+        };
+        // a gas price with an access list makes an EIP-2930 transaction
+        return match &options.access_list {
+            Some(access_list) => tx.with_access_list(access_list.clone()).into(),
+            None => tx.into(),
+        };
     }
     Eip1559TransactionRequest {
         to: address.map(Into::into),
@@ -43,6 +47,7 @@ pub fn create_transaction(
         max_priority_fee_per_gas: options.max_priority_fee_per_gas,
         value: options.value,
         nonce: options.nonce,
+        access_list: options.access_list.clone().unwrap_or_default(),
         ..Default::default()
     }
     .into()
@@ -52,11 +57,104 @@ pub fn send_transaction(
     provider: &WrapProvider,
     signer: &WrapSigner,
     tx: &mut TypedTransaction,
-) -> H256 {
-    fill_transaction(provider, signer, tx, None).unwrap();
-    let rlp = serialize(tx);
-    let tx_hash: H256 = provider.request("eth_sendTransaction", [rlp]).unwrap();
-    tx_hash
+) -> Result<H256, ProviderError> {
+    let from = tx.from().copied().unwrap_or_else(|| signer.address());
+    send_with_nonce_manager(provider, signer, &mut NonceManager::new(from), tx)
+}
+
+/// Sends transactions of the signer in order, with sequential nonces unless they set one,
+/// following the highest nonce set so far.
+/// Stops at the first rejected transaction, as the following ones would have a nonce gap.
+/// Returns the hashes of the transactions sent, and the index and error of the rejected one
+pub fn send_transactions(
+    provider: &WrapProvider,
+    signer: &WrapSigner,
+    txs: &mut [TypedTransaction],
+) -> (Vec<H256>, Option<(usize, ProviderError)>) {
+    let mut nonce_manager = NonceManager::new(signer.address());
+    let mut hashes = Vec::with_capacity(txs.len());
+    for (index, tx) in txs.iter_mut().enumerate() {
+        match send_with_nonce_manager(provider, signer, &mut nonce_manager, tx) {
+            Ok(hash) => hashes.push(hash),
+            Err(error) => return (hashes, Some((index, error))),
+        }
+    }
+    (hashes, None)
+}
+
+fn send_with_nonce_manager(
+    provider: &WrapProvider,
+    signer: &WrapSigner,
+    nonce_manager: &mut NonceManager,
+    tx: &mut TypedTransaction,
+) -> Result<H256, ProviderError> {
+    match tx.nonce() {
+        Some(nonce) => nonce_manager.record(*nonce),
+        None => tx.set_nonce(nonce_manager.next(provider)?),
+    }
+    fill_transaction(provider, signer, tx, None)?;
+    signer.send(tx)
+}
+
+/// Creates a transaction replacing a pending one at the same nonce, with its fees bumped by
+/// `fee_bump` percent, or set to the current network fees if they are higher.
+/// A cancellation is a zero-value transfer from the sender to itself
+pub fn create_replacement_transaction(
+    provider: &WrapProvider,
+    original: &Transaction,
+    fee_bump: u64,
+    cancel: bool,
+) -> Result<TypedTransaction, ProviderError> {
+    let (to, value, data, gas) = if cancel {
+        (Some(original.from), U256::zero(), Bytes::default(), U256::from(21000))
+    } else {
+        (original.to, original.value, original.input.clone(), original.gas)
+    };
+    let bump = |fee: Option<U256>, current: U256| {
+        ethers_utils::bump_fee(fee.unwrap_or_default(), fee_bump).max(current)
+    };
+
+    let tx_type = original.transaction_type.map(|tx_type| tx_type.as_u64());
+    if tx_type == Some(2) {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = provider.estimate_eip1559_fees(None)?;
+        let access_list = match cancel {
+            true => Default::default(),
+            false => original.access_list.clone().unwrap_or_default(),
+        };
+        return Ok(Eip1559TransactionRequest {
+            from: Some(original.from),
+            to: to.map(Into::into),
+            gas: Some(gas),
+            value: Some(value),
+            data: Some(data),
+            nonce: Some(original.nonce),
+            access_list,
+            max_priority_fee_per_gas: Some(bump(
+                original.max_priority_fee_per_gas,
+                max_priority_fee_per_gas,
+            )),
+            max_fee_per_gas: Some(bump(original.max_fee_per_gas, max_fee_per_gas)),
+            ..Default::default()
+        }
+        .into());
+    }
+
+    let gas_price = provider.get_gas_price()?;
+    let tx = TransactionRequest {
+        from: Some(original.from),
+        to: to.map(Into::into),
+        gas: Some(gas),
+        gas_price: Some(bump(original.gas_price, gas_price)),
+        value: Some(value),
+        data: Some(data),
+        nonce: Some(original.nonce),
+        ..Default::default()
+    };
+    // EIP-2930 transactions keep their access list, unless they're cancelled
+    Ok(match (tx_type, &original.access_list) {
+        (Some(1), Some(access_list)) if !cancel => tx.with_access_list(access_list.clone()).into(),
+        _ => tx.into(),
+    })
 }
 
 pub fn create_deploy_contract_transaction(
@@ -72,10 +170,10 @@ pub fn create_deploy_contract_transaction(
         }
         (None, true) => bytecode.clone(),
         (Some(constructor), _) => {
-            let tokens: Vec<Token> = ethers_utils::tokenize_values(&values, &constructor.inputs);
+            let tokens: Vec<Token> = ethers_utils::tokenize_values(values, &constructor.inputs)?;
             constructor
                 .encode_input(bytecode.to_vec(), &tokens)
-                .unwrap()
+                .map_err(ethers_utils::EncodeError::from)?
                 .into()
         }
     };
@@ -90,14 +188,17 @@ pub fn estimate_contract_call_gas(
     method: &str,
     args: &Vec<String>,
     options: &EthersTxOptions,
-) -> U256 {
-    let (_, data): (Function, Bytes) = ethers_utils::encode_function(method, args).unwrap();
+) -> Result<U256, WrapperError> {
+    let (_, data): (Function, Bytes) = ethers_utils::encode_function(method, args)?;
     let mut tx: TypedTransaction = create_transaction(Some(address), data, options);
-    fill_transaction(provider, signer, &mut tx, None).unwrap();
-    if let Some(gas_limit) = tx.as_eip1559_ref().unwrap().gas {
-        return gas_limit;
+    if options.auto_access_list {
+        attach_access_list(provider, signer, &mut tx)?;
     }
-    provider.estimate_gas(&tx, None).unwrap()
+    fill_transaction(provider, signer, &mut tx, None)?;
+    if let Some(gas_limit) = tx.gas() {
+        return Ok(*gas_limit);
+    }
+    Ok(provider.estimate_gas(&tx, None)?)
 }
 
 pub fn call_contract_view(
@@ -105,8 +206,9 @@ pub fn call_contract_view(
     address: Address,
     method: &str,
     args: &Vec<String>,
-) -> Vec<Token> {
-    let (function, data): (Function, Bytes) = ethers_utils::encode_function(method, args).unwrap();
+    overrides: &CallOverrides,
+) -> Result<Vec<Token>, WrapperError> {
+    let (function, data): (Function, Bytes) = ethers_utils::encode_function(method, args)?;
 
     let tx: TypedTransaction = TransactionRequest {
         to: Some(address.into()),
@@ -115,11 +217,14 @@ pub fn call_contract_view(
     }
     .into();
 
-    let bytes: Bytes = provider.call(&tx, None).unwrap();
+    let bytes: Bytes = provider
+        .call_with_overrides(&tx, None, overrides.state.as_ref(), overrides.block.as_ref())?;
 
-    let tokens: Vec<Token> = function.decode_output(&bytes).unwrap();
+    let tokens: Vec<Token> = function
+        .decode_output(&bytes)
+        .map_err(ethers_utils::EncodeError::from)?;
 
-    tokens
+    Ok(tokens)
 }
 
 pub fn call_contract_static(
@@ -129,20 +234,28 @@ pub fn call_contract_static(
     method: &str,
     args: &Vec<String>,
     options: &EthersTxOptions,
+    overrides: &CallOverrides,
 ) -> Result<Vec<Token>, WrapperError> {
     let (function, data): (Function, Bytes) = ethers_utils::encode_function(method, args)?;
 
     let mut tx: TypedTransaction = create_transaction(Some(address), data, options);
-    fill_transaction(provider, signer, &mut tx, None)?;
-    let bytes: Result<Bytes, WrapperError> = provider.call(&tx, None).map_err(|e| e.into());
-
-    if bytes.is_err() {
-        Err(bytes.unwrap_err())
+    if overrides.is_empty() {
+        if options.auto_access_list {
+            attach_access_list(provider, signer, &mut tx)?;
+        }
+        fill_transaction(provider, signer, &mut tx, None)?;
     } else {
-        //TODO (cbrzn): Remove unwrap
-        let tokens: Vec<Token> = function.decode_output(&bytes.unwrap()).unwrap();
-        Ok(tokens)
+        // the gas estimate and access list would be made against the chain's state, where
+        // calls which only succeed with the overrides revert, so the options are used as is
+        tx.set_from(signer.address());
     }
+    let bytes: Bytes = provider
+        .call_with_overrides(&tx, None, overrides.state.as_ref(), overrides.block.as_ref())?;
+
+    let tokens: Vec<Token> = function
+        .decode_output(&bytes)
+        .map_err(ethers_utils::EncodeError::from)?;
+    Ok(tokens)
 }
 
 pub fn call_contract_method(
@@ -152,13 +265,45 @@ pub fn call_contract_method(
     method: &str,
     args: &Vec<String>,
     options: &EthersTxOptions,
-) -> H256 {
-    let (_, encode_data): (Function, Bytes) = ethers_utils::encode_function(method, args).unwrap();
+) -> Result<H256, WrapperError> {
+    let (_, encode_data): (Function, Bytes) = ethers_utils::encode_function(method, args)?;
 
     let mut tx: TypedTransaction =
         create_transaction(Some(address), Bytes::from(encode_data), options);
-    let tx_hash: H256 = send_transaction(provider, signer, &mut tx);
-    tx_hash
+    if options.auto_access_list {
+        attach_access_list(provider, signer, &mut tx)?;
+    }
+    let tx_hash: H256 = send_transaction(provider, signer, &mut tx)?;
+    Ok(tx_hash)
+}
+
+/// Attaches the access list created by `eth_createAccessList` to a transaction without one,
+/// if the transaction uses less gas with the list than without it
+pub fn attach_access_list(
+    provider: &WrapProvider,
+    signer: &WrapSigner,
+    tx: &mut TypedTransaction,
+) -> Result<(), ProviderError> {
+    if matches!(tx.access_list(), Some(list) if !list.0.is_empty()) {
+        return Ok(());
+    }
+    if tx.from().is_none() {
+        tx.set_from(signer.address());
+    }
+    provider.resolve_transaction_to(tx)?;
+
+    let gas = provider.estimate_gas(tx, None)?;
+    let access_list = provider.create_access_list(tx, None)?;
+
+    if access_list.gas_used < gas {
+        // legacy transactions don't have an access list, so they become EIP-2930 ones
+        if let TypedTransaction::Legacy(inner) = tx {
+            *tx = inner.clone().with_access_list(access_list.access_list).into();
+        } else {
+            tx.set_access_list(access_list.access_list);
+        }
+    }
+    Ok(())
 }
 
 /// Helper for filling a transaction's nonce using the wallet
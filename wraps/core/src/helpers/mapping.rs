@@ -1,11 +1,29 @@
 use crate::polywrap_provider::provider::Provider;
 use crate::provider::WrapProvider;
-use crate::wrap::{AccessItem, Log as TxLog, TxReceipt, TxRequest, TxResponse, TxOptions};
+use crate::error::WrapperError;
+use crate::wrap::{
+    AccessItem, AccountOverride as WrapAccountOverride, Block as WrapBlock,
+    BlockOverrides as WrapBlockOverrides, CallArg, CallError, CallErrorArg,
+    CallFrame as WrapCallFrame, CallTracerOptions as WrapCallTracerOptions,
+    DecodedCall as WrapDecodedCall, DecodedLog, EventArg, FeeHistory as WrapFeeHistory,
+    FeePolicy as WrapFeePolicy, FeeSpeed as WrapFeeSpeed, GasMarketSummary as WrapGasMarketSummary,
+    Log as TxLog, LogFilter, PercentileTip, RetryPolicy as WrapRetryPolicy,
+    RetryableError as WrapRetryableError, StorageSlot, TxReceipt, TxRequest, TxResponse,
+    TxOptions, Withdrawal as WrapWithdrawal,
+};
+use ethers_core::abi::Abi;
+use ethers_providers::ProviderError;
+use ethers_utils::{
+    format_token, format_tokens, AccountOverride, BlockOverrides, CallFrame, CallTracerOptions,
+    FeePolicy, FeeSpeed, GasMarketSummary, RetryPolicy, RetryableError, StateOverride,
+};
 use ethers_core::types::{
     transaction::eip2718::TypedTransaction, Bytes, Log, NameOrAddress, Address, Transaction,
-    TransactionReceipt, TransactionRequest, H160, H256, U64, U256, Eip1559TransactionRequest
+    TransactionReceipt, TransactionRequest, H160, H256, U64, U256, Eip1559TransactionRequest,
+    Block, BlockId, BlockNumber, FeeHistory, Filter, TxHash, ValueOrArray
 };
-use polywrap_wasm_rs::{BigInt, BigIntWrapper};
+use polywrap_wasm_rs::{BigInt, BigIntWrapper, BigNumber};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use ethers_core::types::transaction::eip2930::{AccessList, AccessListItem};
 
@@ -16,21 +34,57 @@ pub struct EthersTxOptions {
     pub gas_price: Option<U256>,
     pub value: Option<U256>,
     pub nonce: Option<U256>,
+    pub access_list: Option<AccessList>,
+    pub auto_access_list: bool,
+    pub fee_policy: Option<FeePolicy>,
+}
+
+/// State and block overrides of `eth_call`
+#[derive(Default)]
+pub struct CallOverrides {
+    pub state: Option<StateOverride>,
+    pub block: Option<BlockOverrides>,
+}
+
+impl CallOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.state.is_none() && self.block.is_none()
+    }
+}
+
+fn bigintwrapper_to_u256(big_int: &BigIntWrapper) -> Result<U256, String> {
+    U256::from_dec_str(&big_int.0.to_string())
+        .map_err(|e| format!("Invalid uint256: {}. Error: {}", big_int.0, e))
 }
 
-fn bigintwrapper_to_u256(big_int: &BigIntWrapper) -> U256 {
-    U256::from_str_radix(&big_int.0.to_string(), 10).unwrap()
+fn bigintwrapper_to_u64(big_int: &BigIntWrapper) -> Result<u64, String> {
+    big_int
+        .0
+        .to_string()
+        .parse()
+        .map_err(|e| format!("Invalid uint64: {}. Error: {}", big_int.0, e))
 }
 
-pub fn from_wrap_tx_options(maybe_options: Option<TxOptions>) -> EthersTxOptions {
-    match maybe_options {
+pub fn from_wrap_tx_options(maybe_options: Option<TxOptions>) -> Result<EthersTxOptions, String> {
+    Ok(match maybe_options {
         Some(options) => EthersTxOptions {
-            gas_limit: options.gas_limit.map(|big_int| bigintwrapper_to_u256(&big_int)),
-            max_fee_per_gas: options.max_fee_per_gas.map(|big_int| bigintwrapper_to_u256(&big_int)),
-            max_priority_fee_per_gas: options.max_priority_fee_per_gas.map(|big_int| bigintwrapper_to_u256(&big_int)),
-            gas_price: options.gas_price.map(|big_int| bigintwrapper_to_u256(&big_int)),
-            value: options.value.map(|big_int| bigintwrapper_to_u256(&big_int)),
+            gas_limit: options.gas_limit.as_ref().map(bigintwrapper_to_u256).transpose()?,
+            max_fee_per_gas: options
+                .max_fee_per_gas
+                .as_ref()
+                .map(bigintwrapper_to_u256)
+                .transpose()?,
+            max_priority_fee_per_gas: options
+                .max_priority_fee_per_gas
+                .as_ref()
+                .map(bigintwrapper_to_u256)
+                .transpose()?,
+            gas_price: options.gas_price.as_ref().map(bigintwrapper_to_u256).transpose()?,
+            value: options.value.as_ref().map(bigintwrapper_to_u256).transpose()?,
             nonce: options.nonce.map(Into::into),
+            access_list: options.access_list.map(from_wrap_access_list).transpose()?,
+            auto_access_list: options.auto_access_list.unwrap_or(false),
+            fee_policy: options.fee_policy.map(from_wrap_fee_policy).transpose()?,
         },
         None => EthersTxOptions {
             gas_limit: None,
@@ -39,93 +93,569 @@ pub fn from_wrap_tx_options(maybe_options: Option<TxOptions>) -> EthersTxOptions
             gas_price: None,
             value: None,
             nonce: None,
+            access_list: None,
+            auto_access_list: false,
+            fee_policy: None,
         }
-    }
+    })
 }
 
-pub fn from_wrap_request(request: TxRequest) -> TypedTransaction {
-    if request.gas_price.is_some() {
-        TransactionRequest {
-            from: request.from.map(|v| H160::from_str(&v).unwrap()),
-            to: request
-                .to
-                .map(|v| NameOrAddress::Address(H160::from_str(&v).unwrap())),
-            gas: request
-                .gas_limit
-                .map(|v| bigintwrapper_to_u256(&v)),
-            value: request
-                .value
-                .map(|v| bigintwrapper_to_u256(&v)),
-            data: request.data.map(|v| Bytes::from_str(&v).unwrap()),
-            nonce: request.nonce.map(Into::into),
-            gas_price: request
-                .gas_price
-                .map(|v| bigintwrapper_to_u256(&v)),
-            chain_id: request
-                .chain_id
-                .map(|v| U64::from_str(&v.0.to_string()).unwrap()),
-        }.into()
-    } else {
-        let access_list = match request.access_list {
-            Some(wrap_access_list) => {
-                let items: Vec<AccessListItem> = wrap_access_list
+/// Fields of the policy override the ones of its speed preset
+pub fn from_wrap_fee_policy(policy: WrapFeePolicy) -> Result<FeePolicy, String> {
+    let speed = match policy.speed {
+        Some(WrapFeeSpeed::SLOW) => FeeSpeed::Slow,
+        Some(WrapFeeSpeed::FAST) => FeeSpeed::Fast,
+        _ => FeeSpeed::Standard,
+    };
+    let preset = FeePolicy::preset(speed);
+    let fee_policy = FeePolicy {
+        block_count: policy.block_count.map(Into::into).unwrap_or(preset.block_count),
+        reward_percentiles: policy
+            .reward_percentiles
+            .map(|percentiles| from_wrap_percentiles(&percentiles))
+            .transpose()?
+            .unwrap_or(preset.reward_percentiles),
+        base_fee_multiplier: policy
+            .base_fee_multiplier
+            .as_ref()
+            .map(bignumber_to_f64)
+            .transpose()?
+            .unwrap_or(preset.base_fee_multiplier),
+        min_priority_fee: policy.min_priority_fee.as_ref().map(bigintwrapper_to_u256).transpose()?,
+        max_priority_fee: policy.max_priority_fee.as_ref().map(bigintwrapper_to_u256).transpose()?,
+    };
+    fee_policy.check()?;
+    Ok(fee_policy)
+}
+
+/// Fields the policy doesn't set are the ones of the preset policy
+pub fn from_wrap_retry_policy(policy: &WrapRetryPolicy) -> RetryPolicy {
+    let preset = RetryPolicy::preset();
+    RetryPolicy {
+        max_attempts: policy.max_attempts.unwrap_or(preset.max_attempts).max(1),
+        retry_on: policy
+            .retry_on
+            .as_ref()
+            .map(|errors| {
+                errors
                     .iter()
-                    .map(|access_item| {
-                        let address: Address = Address::from_str(access_item.address.as_str()).unwrap();
-                        let storage_keys: Vec<H256> = access_item.storage_keys
-                            .iter()
-                            .map(|key| H256::from_str(key.as_str()).unwrap())
-                            .collect();
-                        AccessListItem { address, storage_keys }
+                    .map(|error| match error {
+                        WrapRetryableError::RATE_LIMITED => RetryableError::RateLimited,
+                        WrapRetryableError::TIMEOUT => RetryableError::Timeout,
+                        WrapRetryableError::HEADER_NOT_FOUND => RetryableError::HeaderNotFound,
+                        _ => RetryableError::Transport,
                     })
-                    .collect();
-                AccessList(items)
-            }
-            None => AccessList::default()
-        };
-        Eip1559TransactionRequest {
-            from: request.from.map(|v| H160::from_str(&v).unwrap()),
-            to: request
-                .to
-                .map(|v| NameOrAddress::Address(H160::from_str(&v).unwrap())),
-            gas: request
-                .gas_limit
-                .map(|v| bigintwrapper_to_u256(&v)),
-            value: request
-                .value
-                .map(|v| bigintwrapper_to_u256(&v)),
-            data: request.data.map(|v| Bytes::from_str(&v).unwrap()),
-            nonce: request.nonce.map(Into::into),
-            access_list,
+                    .collect()
+            })
+            .unwrap_or(preset.retry_on),
+    }
+}
+
+fn bignumber_to_f64(big_number: &BigNumber) -> Result<f64, String> {
+    big_number
+        .to_string()
+        .parse()
+        .map_err(|e| format!("Invalid number: {}. Error: {}", big_number, e))
+}
+
+pub fn u256_to_bigintwrapper(value: &U256) -> BigIntWrapper {
+    // built from the 64 bits words of the value, the most significant one first
+    let big_int = value.0.iter().rev().fold(BigInt::from(0u64), |big_int, word| {
+        (big_int << 64u32) + *word
+    });
+    BigIntWrapper(big_int)
+}
+
+pub fn u64_to_bigintwrapper(value: U64) -> BigIntWrapper {
+    BigIntWrapper(BigInt::from(value.as_u64()))
+}
+
+// the floats mapped are finite, as they come from JSON numbers or validated percentiles, so
+// their decimal representation always parses
+fn f64_to_bignumber(value: f64) -> BigNumber {
+    BigNumber::from_str(&value.to_string()).unwrap_or_default()
+}
+
+pub fn from_wrap_percentiles(percentiles: &[BigNumber]) -> Result<Vec<f64>, String> {
+    let percentiles = percentiles.iter().map(bignumber_to_f64).collect::<Result<Vec<_>, _>>()?;
+    ethers_utils::check_reward_percentiles(&percentiles)?;
+    Ok(percentiles)
+}
+
+pub fn to_wrap_fee_history(fee_history: &FeeHistory) -> WrapFeeHistory {
+    WrapFeeHistory {
+        oldest_block: u256_to_bigintwrapper(&fee_history.oldest_block),
+        base_fee_per_gas: fee_history.base_fee_per_gas.iter().map(u256_to_bigintwrapper).collect(),
+        gas_used_ratio: fee_history.gas_used_ratio.iter().copied().map(f64_to_bignumber).collect(),
+        reward: fee_history
+            .reward
+            .iter()
+            .map(|block| block.iter().map(u256_to_bigintwrapper).collect())
+            .collect(),
+    }
+}
+
+pub fn to_wrap_gas_market_summary(summary: &GasMarketSummary) -> WrapGasMarketSummary {
+    WrapGasMarketSummary {
+        block_number: u256_to_bigintwrapper(&summary.block_number),
+        base_fee_per_gas: u256_to_bigintwrapper(&summary.base_fee_per_gas),
+        next_base_fee_per_gas: u256_to_bigintwrapper(&summary.next_base_fee_per_gas),
+        tips: summary
+            .tips
+            .iter()
+            .map(|(percentile, tip)| PercentileTip {
+                percentile: f64_to_bignumber(*percentile),
+                max_priority_fee_per_gas: u256_to_bigintwrapper(tip),
+            })
+            .collect(),
+    }
+}
+
+pub fn from_wrap_request(request: TxRequest) -> Result<TypedTransaction, String> {
+    let access_list = request.access_list.map(from_wrap_access_list).transpose()?;
+    // without a type, a gas price makes a legacy transaction, or an EIP-2930 one with an access list
+    let tx_type = match (request._type, &request.gas_price, &access_list) {
+        (Some(tx_type), _, _) => tx_type,
+        (None, Some(_), Some(_)) => 1,
+        (None, Some(_), None) => 0,
+        (None, None, _) => 2,
+    };
+    let legacy = TransactionRequest {
+        from: request
+            .from
+            .as_ref()
+            .map(|v| H160::from_str(v).map_err(|e| format!("Invalid address: {}. Error: {}", v, e)))
+            .transpose()?,
+        to: request.to.clone().map(to_name_or_address).transpose()?,
+        gas: request
+            .gas_limit
+            .as_ref()
+            .map(bigintwrapper_to_u256)
+            .transpose()?,
+        value: request
+            .value
+            .as_ref()
+            .map(bigintwrapper_to_u256)
+            .transpose()?,
+        data: request
+            .data
+            .as_ref()
+            .map(|v| Bytes::from_str(v).map_err(|e| format!("Invalid data: {}. Error: {}", v, e)))
+            .transpose()?,
+        nonce: request.nonce.map(Into::into),
+        gas_price: request
+            .gas_price
+            .as_ref()
+            .map(bigintwrapper_to_u256)
+            .transpose()?,
+        chain_id: request
+            .chain_id
+            .as_ref()
+            .map(|v| bigintwrapper_to_u64(v).map(U64::from))
+            .transpose()?,
+    };
+    match tx_type {
+        0 if matches!(&access_list, Some(list) if !list.0.is_empty()) => Err(
+            "Legacy transactions (type 0) can't have an access list, use type 1 instead"
+                .to_string(),
+        ),
+        0 => Ok(legacy.into()),
+        1 => Ok(legacy.with_access_list(access_list.unwrap_or_default()).into()),
+        2 => Ok(Eip1559TransactionRequest {
+            from: legacy.from,
+            to: legacy.to,
+            gas: legacy.gas,
+            value: legacy.value,
+            data: legacy.data,
+            nonce: legacy.nonce,
+            access_list: access_list.unwrap_or_default(),
             max_fee_per_gas: request
                 .max_fee_per_gas
-                .map(|v| bigintwrapper_to_u256(&v)),
+                .as_ref()
+                .map(bigintwrapper_to_u256)
+                .transpose()?,
             max_priority_fee_per_gas: request
                 .max_priority_fee_per_gas
-                .map(|v| bigintwrapper_to_u256(&v)),
-            chain_id: request
-                .chain_id
-                .map(|v| U64::from_str(&v.0.to_string()).unwrap()),
-        }.into()
+                .as_ref()
+                .map(bigintwrapper_to_u256)
+                .transpose()?,
+            chain_id: legacy.chain_id,
+        }.into()),
+        tx_type => Err(format!("Unsupported transaction type: {}", tx_type)),
+    }
+}
+
+pub fn from_wrap_access_list(access_list: Vec<AccessItem>) -> Result<AccessList, String> {
+    let items: Vec<AccessListItem> = access_list
+        .iter()
+        .map(|access_item| {
+            let address: Address = Address::from_str(access_item.address.as_str())
+                .map_err(|e| format!("Invalid address: {}. Error: {}", access_item.address, e))?;
+            let storage_keys: Vec<H256> = access_item.storage_keys
+                .iter()
+                .map(|key| {
+                    H256::from_str(key.as_str())
+                        .map_err(|e| format!("Invalid storage key: {}. Error: {}", key, e))
+                })
+                .collect::<Result<Vec<H256>, String>>()?;
+            Ok(AccessListItem { address, storage_keys })
+        })
+        .collect::<Result<Vec<AccessListItem>, String>>()?;
+    Ok(AccessList(items))
+}
+
+pub fn to_wrap_access_list(access_list: &AccessList) -> Vec<AccessItem> {
+    access_list
+        .0
+        .iter()
+        .map(|item| AccessItem {
+            address: format!("{:?}", item.address),
+            storage_keys: item.storage_keys.iter().map(|k| format!("{:?}", k)).collect(),
+        })
+        .collect()
+}
+
+// recipients containing a dot are ENS names, resolved when filling the transaction
+fn to_name_or_address(value: String) -> Result<NameOrAddress, String> {
+    if value.contains('.') {
+        Ok(NameOrAddress::Name(value))
+    } else {
+        H160::from_str(&value)
+            .map(NameOrAddress::Address)
+            .map_err(|e| format!("Invalid address: {}. Error: {}", value, e))
+    }
+}
+
+/// Parses an address, resolving it through ENS if it is a name (e.g. "vitalik.eth")
+pub fn resolve_address(provider: &WrapProvider, address: &str) -> Result<Address, WrapperError> {
+    match Address::from_str(address) {
+        Ok(addr) => Ok(addr),
+        Err(_) if address.contains('.') => Ok(provider.resolve_name(address)?),
+        Err(e) => Err(WrapperError::InvalidInput(format!(
+            "Invalid address: {}. Error: {}",
+            address, e
+        ))),
+    }
+}
+
+pub fn from_wrap_call_overrides(
+    state_overrides: Option<Vec<WrapAccountOverride>>,
+    block_overrides: Option<WrapBlockOverrides>,
+) -> Result<CallOverrides, String> {
+    let state = match state_overrides {
+        Some(accounts) => {
+            let accounts = accounts
+                .into_iter()
+                .map(from_wrap_account_override)
+                .collect::<Result<Vec<(Address, AccountOverride)>, String>>()?;
+            Some(ethers_utils::state_override(accounts).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    let block = block_overrides.map(from_wrap_block_overrides).transpose()?;
+    Ok(CallOverrides { state, block })
+}
+
+fn from_wrap_account_override(
+    account: WrapAccountOverride,
+) -> Result<(Address, AccountOverride), String> {
+    let address = Address::from_str(&account.address)
+        .map_err(|e| format!("Invalid address: {}. Error: {}", account.address, e))?;
+    let code = match account.code {
+        Some(code) => Some(
+            Bytes::from_str(&code).map_err(|e| format!("Invalid code: {}. Error: {}", code, e))?,
+        ),
+        None => None,
+    };
+    Ok((
+        address,
+        AccountOverride {
+            balance: account.balance.as_ref().map(bigintwrapper_to_u256).transpose()?,
+            nonce: account.nonce.as_ref().map(bigintwrapper_to_u64).transpose()?.map(Into::into),
+            code,
+            state: account.state.map(from_wrap_storage_slots).transpose()?,
+            state_diff: account.state_diff.map(from_wrap_storage_slots).transpose()?,
+        },
+    ))
+}
+
+fn from_wrap_storage_slots(slots: Vec<StorageSlot>) -> Result<BTreeMap<H256, H256>, String> {
+    slots
+        .iter()
+        .map(|storage| {
+            let slot = H256::from_str(&storage.slot)
+                .map_err(|e| format!("Invalid storage slot: {}. Error: {}", storage.slot, e))?;
+            let value = H256::from_str(&storage.value)
+                .map_err(|e| format!("Invalid storage value: {}. Error: {}", storage.value, e))?;
+            Ok((slot, value))
+        })
+        .collect()
+}
+
+fn from_wrap_block_overrides(overrides: WrapBlockOverrides) -> Result<BlockOverrides, String> {
+    let coinbase = match overrides.coinbase {
+        Some(coinbase) => Some(
+            Address::from_str(&coinbase)
+                .map_err(|e| format!("Invalid coinbase: {}. Error: {}", coinbase, e))?,
+        ),
+        None => None,
+    };
+    let random = match overrides.random {
+        Some(random) => Some(
+            H256::from_str(&random)
+                .map_err(|e| format!("Invalid random: {}. Error: {}", random, e))?,
+        ),
+        None => None,
+    };
+    Ok(BlockOverrides {
+        number: overrides.number.as_ref().map(bigintwrapper_to_u256).transpose()?,
+        time: overrides.time.as_ref().map(bigintwrapper_to_u64).transpose()?.map(Into::into),
+        gas_limit: overrides
+            .gas_limit
+            .as_ref()
+            .map(bigintwrapper_to_u64)
+            .transpose()?
+            .map(Into::into),
+        coinbase,
+        random,
+        base_fee: overrides.base_fee.as_ref().map(bigintwrapper_to_u256).transpose()?,
+    })
+}
+
+/// Parses a block number (decimal or hex), hash or tag. Latest when `None`
+pub fn from_wrap_block_id(block: Option<String>) -> Result<BlockId, String> {
+    match block {
+        Some(block) => BlockId::from_str(&block)
+            .map_err(|e| format!("Invalid block: {}. Error: {}", block, e)),
+        None => Ok(BlockNumber::Latest.into()),
+    }
+}
+
+/// Parses a block number (decimal or hex) or tag, like "latest" or "finalized"
+pub fn from_wrap_block_number(block: &str) -> Result<BlockNumber, String> {
+    BlockNumber::from_str(block).map_err(|e| format!("Invalid block: {}. Error: {}", block, e))
+}
+
+pub fn from_wrap_log_filter(filter: LogFilter) -> Result<Filter, String> {
+    let mut ethers_filter = Filter::new();
+
+    if let Some(hash) = filter.block_hash {
+        let hash = H256::from_str(&hash)
+            .map_err(|e| format!("Invalid block hash: {}. Error: {}", hash, e))?;
+        ethers_filter = ethers_filter.at_block_hash(hash);
+    } else {
+        if let Some(from_block) = filter.from_block {
+            ethers_filter = ethers_filter.from_block(from_wrap_block_number(&from_block)?);
+        }
+        if let Some(to_block) = filter.to_block {
+            ethers_filter = ethers_filter.to_block(from_wrap_block_number(&to_block)?);
+        }
     }
+
+    if let Some(addresses) = filter.address {
+        let addresses = addresses
+            .iter()
+            .map(|v| Address::from_str(v).map_err(|e| format!("Invalid address: {}. Error: {}", v, e)))
+            .collect::<Result<Vec<Address>, String>>()?;
+        ethers_filter = match addresses.len() {
+            1 => ethers_filter.address(addresses[0]),
+            _ => ethers_filter.address(addresses),
+        };
+    }
+
+    if let Some(topics) = filter.topics {
+        if topics.len() > 4 {
+            return Err(format!("Too many topics: {}. A log has at most 4 topics", topics.len()));
+        }
+        for (position, topic) in topics.into_iter().enumerate() {
+            // a null position is a wildcard and matches any topic
+            let values = match topic {
+                Some(values) => values,
+                None => continue,
+            };
+            let hashes = values
+                .iter()
+                .map(|v| {
+                    H256::from_str(v)
+                        .map(Some)
+                        .map_err(|e| format!("Invalid topic: {}. Error: {}", v, e))
+                })
+                .collect::<Result<Vec<Option<H256>>, String>>()?;
+            ethers_filter.topics[position] = Some(match hashes.len() {
+                1 => ValueOrArray::Value(hashes[0]),
+                _ => ValueOrArray::Array(hashes),
+            });
+        }
+    }
+
+    Ok(ethers_filter)
 }
 
-fn to_wrap_log(log: &Log) -> TxLog {
-    TxLog {
-        block_number: BigIntWrapper(BigInt::from_str(&log.block_number.unwrap().to_string()).unwrap()),
-        block_hash: format!("{:#x}", log.block_hash.unwrap()),
-        transaction_index: log.transaction_index.unwrap().as_u32(),
-        removed: log.removed.unwrap(),
+/// Maps a log of a mined transaction. Pending logs, which aren't in a block yet, are `None`
+pub fn to_wrap_log(log: &Log) -> Option<TxLog> {
+    Some(TxLog {
+        block_number: u64_to_bigintwrapper(log.block_number?),
+        block_hash: format!("{:#x}", log.block_hash?),
+        transaction_index: log.transaction_index?.as_u32(),
+        removed: log.removed.unwrap_or(false),
         address: format!("{:#x}", log.address),
-        data: format!("{:?}", log.data),
+        data: format!("{}", log.data),
         topics: log.topics.iter().map(|v| format!("{:#x}", v)).collect(),
-        transaction_hash: format!("{:#x}", log.transaction_hash.unwrap()),
-        log_index: log.log_index.unwrap().as_u32(),
+        transaction_hash: format!("{:#x}", log.transaction_hash?),
+        log_index: log.log_index?.as_u32(),
+    })
+}
+
+pub fn to_wrap_decoded_logs(
+    abi: &Abi,
+    logs: &[TxLog],
+) -> Result<Vec<DecodedLog>, WrapperError> {
+    let mut decoded_logs: Vec<DecodedLog> = vec![];
+    for log in logs {
+        let topics = log
+            .topics
+            .iter()
+            .map(|v| H256::from_str(v).map_err(|e| format!("Invalid topic: {}. Error: {}", v, e)))
+            .collect::<Result<Vec<H256>, String>>()?;
+        let data = Bytes::from_str(&log.data)
+            .map_err(|e| format!("Invalid log data: {}. Error: {}", log.data, e))?;
+
+        // logs which don't match their event in the ABI are ABI errors, not invalid input
+        let decoded = ethers_utils::decode_log(abi, topics, data.to_vec()).map_err(|e| {
+            WrapperError::ContractError(format!("Error decoding log {}: {}", log.log_index, e))
+        })?;
+        if let Some((event, parsed)) = decoded {
+            let args = event
+                .inputs
+                .iter()
+                .zip(parsed.params.iter())
+                .map(|(input, param)| EventArg {
+                    name: param.name.clone(),
+                    _type: input.kind.to_string(),
+                    indexed: input.indexed,
+                    value: format_token(&param.value),
+                })
+                .collect();
+            decoded_logs.push(DecodedLog {
+                log: log.clone(),
+                name: event.name.clone(),
+                signature: ethers_utils::event_signature(&event),
+                args,
+            });
+        }
+    }
+    Ok(decoded_logs)
+}
+
+pub fn from_wrap_call_tracer_options(
+    options: Option<WrapCallTracerOptions>,
+) -> CallTracerOptions {
+    match options {
+        Some(options) => CallTracerOptions {
+            only_top_call: options.only_top_call.unwrap_or(false),
+            timeout: options.timeout,
+        },
+        None => CallTracerOptions::default(),
     }
 }
 
-pub fn to_wrap_receipt(receipt: TransactionReceipt, confirmations: u32) -> TxReceipt {
-    TxReceipt {
+/// Maps a call frame and its internal calls, decoding the ones matching a function of `abi`
+pub fn to_wrap_call_frame(frame: &CallFrame, abi: Option<&Abi>) -> WrapCallFrame {
+    // the output of a failed call is its revert data
+    let output = match frame.error {
+        Some(_) => None,
+        None => frame.output.as_ref().map(|output| output.as_ref()),
+    };
+    let decoded = abi
+        .and_then(|abi| ethers_utils::decode_call(abi, &frame.input, output))
+        .map(|call| WrapDecodedCall {
+            name: call.function.name.clone(),
+            signature: ethers_utils::function_signature(&call.function),
+            args: call
+                .function
+                .inputs
+                .iter()
+                .zip(call.args.iter())
+                .map(|(input, token)| CallArg {
+                    name: input.name.clone(),
+                    _type: input.kind.to_string(),
+                    value: format_token(token),
+                })
+                .collect(),
+            result: call.result.as_deref().map(format_tokens),
+        });
+    WrapCallFrame {
+        _type: frame.call_type.clone(),
+        from: format!("{:#x}", frame.from),
+        to: frame.to.map(|to| format!("{:#x}", to)),
+        value: frame.value.as_ref().map(u256_to_bigintwrapper),
+        gas: u256_to_bigintwrapper(&frame.gas),
+        gas_used: u256_to_bigintwrapper(&frame.gas_used),
+        input: format!("{}", frame.input),
+        output: frame.output.as_ref().map(|output| format!("{}", output)),
+        error: frame.error.clone(),
+        revert_reason: frame.revert_reason.clone(),
+        decoded,
+        calls: frame.calls.iter().map(|call| to_wrap_call_frame(call, abi)).collect(),
+    }
+}
+
+pub fn to_wrap_call_error(error: &WrapperError, abi: Option<&Abi>) -> CallError {
+    let message = error.message();
+    let call_error = |kind: &str, message: String| CallError {
+        kind: kind.to_string(),
+        name: None,
+        message,
+        args: None,
+        data: None,
+    };
+
+    // geth reports "execution reverted" while ganache reports "revert"
+    if !message.contains("revert") {
+        return call_error("Other", message);
+    }
+    let data = match ethers_utils::find_revert_data(&message) {
+        Some(data) => data,
+        None => return call_error("Revert", message),
+    };
+
+    let revert = ethers_utils::decode_revert(&data, abi);
+    let args = revert
+        .args
+        .iter()
+        .map(|(param, token)| CallErrorArg {
+            name: param.name.clone(),
+            _type: param.kind.to_string(),
+            value: format_token(token),
+        })
+        .collect();
+    CallError {
+        kind: revert.kind.to_string(),
+        name: revert.name,
+        message: revert.reason.unwrap_or(message),
+        args: Some(args),
+        data: Some(format!("{}", Bytes::from(data))),
+    }
+}
+
+pub fn to_wrap_receipt(
+    receipt: TransactionReceipt,
+    confirmations: u32,
+) -> Result<TxReceipt, WrapperError> {
+    // receipts of pending transactions, returned by some nodes, have no block
+    let (gas_used, block_number, block_hash) =
+        match (receipt.gas_used, receipt.block_number, receipt.block_hash) {
+            (Some(gas_used), Some(block_number), Some(block_hash)) => {
+                (gas_used, block_number, block_hash)
+            }
+            _ => {
+                return Err(WrapperError::NotFound(format!(
+                    "Transaction {:#x} is not mined",
+                    receipt.transaction_hash
+                )))
+            }
+        };
+    Ok(TxReceipt {
         to: match receipt.to {
             Some(addr) => format!("{:#x}", addr),
             None => "".to_owned(),
@@ -137,77 +667,131 @@ pub fn to_wrap_receipt(receipt: TransactionReceipt, confirmations: u32) -> TxRec
         },
         transaction_index: receipt.transaction_index.as_u32(),
         root: receipt.root.map(|v| format!("{:#x}", v)),
-        gas_used: BigIntWrapper(BigInt::from_str(&receipt.gas_used.unwrap().to_string()).unwrap()),
+        gas_used: u256_to_bigintwrapper(&gas_used),
         logs_bloom: format!("{:#x}", receipt.logs_bloom),
         transaction_hash: format!("{:#x}", receipt.transaction_hash),
-        logs: receipt.logs.iter().map(to_wrap_log).collect(),
-        block_number: BigIntWrapper(BigInt::from_str(&receipt.block_number.unwrap().to_string()).unwrap()),
-        block_hash: format!("{:#x}", receipt.block_hash.unwrap()),
+        logs: receipt.logs.iter().filter_map(to_wrap_log).collect(),
+        block_number: u64_to_bigintwrapper(block_number),
+        block_hash: format!("{:#x}", block_hash),
         confirmations,
-        cumulative_gas_used: BigIntWrapper(BigInt::from_str(&receipt.cumulative_gas_used.to_string()).unwrap()),
-        effective_gas_price: BigIntWrapper(BigInt::from_str(&match receipt.effective_gas_price {
-            Some(price) => price.to_string(),
-            None => "0".to_owned(),
-        })
-        .unwrap()),
+        cumulative_gas_used: u256_to_bigintwrapper(&receipt.cumulative_gas_used),
+        effective_gas_price: u256_to_bigintwrapper(&receipt.effective_gas_price.unwrap_or_default()),
         _type: match receipt.transaction_type {
             Some(v) => v.as_u32(),
             _ => 0,
         },
         status: receipt.status.map(|v| v.as_u32()),
-    }
+        decoded_logs: None,
+    })
 }
 
 pub fn to_wrap_response(
     provider: &WrapProvider,
     response: Transaction,
-) -> TxResponse {
+) -> Result<TxResponse, WrapperError> {
     let block = match response.block_hash {
-        Some(h) => provider.get_block(h).ok(),
+        Some(h) => provider.get_block(h).ok().flatten(),
         None => None,
-    }.flatten();
-    let chain_id = provider.get_chainid().unwrap();
-    let max_fee_per_gas = response
-        .max_fee_per_gas
-        .map(|v| BigIntWrapper(BigInt::from_str(&v.to_string()).unwrap()));
-    let max_priority_fee_per_gas = response
-        .max_priority_fee_per_gas
-        .map(|v| BigIntWrapper(BigInt::from_str(&v.to_string()).unwrap()));
-    let gas_price = response
-        .gas_price
-        .map(|v| BigIntWrapper(BigInt::from_str(&v.to_string()).unwrap()));
-    let access_list: Option<Vec<AccessItem>> = response.access_list.as_ref().map(|v| {
-        v.0.iter()
-            .map(|i| AccessItem {
-                address: serde_json::to_string(&i.address).unwrap(),
-                storage_keys: i
-                    .storage_keys
-                    .iter()
-                    .map(|k| serde_json::to_string(&k).unwrap())
-                    .collect(),
-            })
-            .collect()
-    });
+    };
+    // the chain id is only fetched for transactions without one
+    let chain_id = match response.chain_id {
+        Some(_) => U256::zero(),
+        None => provider.get_chainid()?,
+    };
+    Ok(to_wrap_transaction(response, chain_id, block.map(|v| v.timestamp)))
+}
+
+/// Maps a transaction, with the chain id used if it doesn't have one and the timestamp of
+/// its block
+fn to_wrap_transaction(
+    response: Transaction,
+    chain_id: U256,
+    timestamp: Option<U256>,
+) -> TxResponse {
+    let max_fee_per_gas = response.max_fee_per_gas.as_ref().map(u256_to_bigintwrapper);
+    let max_priority_fee_per_gas =
+        response.max_priority_fee_per_gas.as_ref().map(u256_to_bigintwrapper);
+    let gas_price = response.gas_price.as_ref().map(u256_to_bigintwrapper);
+    let access_list: Option<Vec<AccessItem>> = response.access_list.as_ref().map(to_wrap_access_list);
     TxResponse {
         hash: format!("{:#x}", response.hash),
         to: response.to.map(|v| format!("{:#x}", v)),
         from: format!("{:#x}", response.from),
         nonce: response.nonce.as_u32(),
-        gas_limit: BigIntWrapper(BigInt::from_str(&response.gas.to_string()).unwrap()),
+        gas_limit: u256_to_bigintwrapper(&response.gas),
         max_fee_per_gas,
         max_priority_fee_per_gas,
         gas_price,
-        value: BigIntWrapper(BigInt::from_str(&response.value.to_string()).unwrap()),
-        chain_id: BigIntWrapper(BigInt::from_str(&response.chain_id.unwrap_or(chain_id).to_string()).unwrap()),
-        block_number: response
-            .block_number
-            .map(|n| BigIntWrapper(BigInt::from_str(&n.to_string()).unwrap())),
+        value: u256_to_bigintwrapper(&response.value),
+        chain_id: u256_to_bigintwrapper(&response.chain_id.unwrap_or(chain_id)),
+        block_number: response.block_number.map(u64_to_bigintwrapper),
         block_hash: response.block_hash.map(|v| format!("{:#x}", v)),
-        timestamp: block.map(|v| v.timestamp.as_u32()),
-        r: Some(response.v.to_string()),
-        s: Some(response.v.to_string()),
+        timestamp: timestamp.map(|v| v.as_u32()),
+        r: Some(format!("{:#x}", response.r)),
+        s: Some(format!("{:#x}", response.s)),
         v: Some(response.v.as_u32()),
         _type: response.transaction_type.map(|v| v.as_u32()),
         access_list,
     }
-}
\ No newline at end of file
+}
+
+pub fn to_wrap_block(block: &Block<TxHash>) -> Result<WrapBlock, WrapperError> {
+    let transactions = block.transactions.iter().map(|v| format!("{:#x}", v)).collect();
+    to_wrap_block_fields(block, transactions, None)
+}
+
+/// Maps a block with its full transactions, using `chain_id` for the ones without chain id
+pub fn to_wrap_block_with_transactions(
+    block: &Block<Transaction>,
+    chain_id: U256,
+) -> Result<WrapBlock, WrapperError> {
+    let transactions = block.transactions.iter().map(|v| format!("{:#x}", v.hash)).collect();
+    let full_transactions = block
+        .transactions
+        .iter()
+        .map(|tx| to_wrap_transaction(tx.clone(), chain_id, Some(block.timestamp)))
+        .collect();
+    to_wrap_block_fields(block, transactions, Some(full_transactions))
+}
+
+fn to_wrap_block_fields<TX>(
+    block: &Block<TX>,
+    transactions: Vec<String>,
+    full_transactions: Option<Vec<TxResponse>>,
+) -> Result<WrapBlock, WrapperError> {
+    let withdrawals = match ethers_utils::block_withdrawals(&block.other) {
+        Some(withdrawals) => Some(
+            withdrawals
+                .map_err(|e| {
+                    // the block comes from the node, so its response couldn't be read
+                    let error = format!("Invalid block withdrawals: {}", e);
+                    WrapperError::ProviderError(ProviderError::CustomError(error))
+                })?
+                .iter()
+                .map(|withdrawal| WrapWithdrawal {
+                    index: u64_to_bigintwrapper(withdrawal.index),
+                    validator_index: u64_to_bigintwrapper(withdrawal.validator_index),
+                    address: format!("{:#x}", withdrawal.address),
+                    amount: u256_to_bigintwrapper(&withdrawal.amount),
+                })
+                .collect(),
+        ),
+        None => None,
+    };
+    Ok(WrapBlock {
+        number: block.number.map(u64_to_bigintwrapper),
+        hash: block.hash.map(|v| format!("{:#x}", v)),
+        parent_hash: format!("{:#x}", block.parent_hash),
+        timestamp: u256_to_bigintwrapper(&block.timestamp),
+        nonce: block.nonce.map(|v| format!("{:#x}", v)),
+        difficulty: u256_to_bigintwrapper(&block.difficulty),
+        gas_limit: u256_to_bigintwrapper(&block.gas_limit),
+        gas_used: u256_to_bigintwrapper(&block.gas_used),
+        miner: block.author.map(|v| format!("{:#x}", v)),
+        extra_data: format!("{}", block.extra_data),
+        base_fee_per_gas: block.base_fee_per_gas.as_ref().map(u256_to_bigintwrapper),
+        withdrawals,
+        transactions,
+        full_transactions,
+    })
+}
@@ -1,2 +1 @@
-pub mod format;
 pub mod mapping;
\ No newline at end of file
@@ -1,8 +1,9 @@
 use ethers_core::abi::Abi;
-use ethers_core::types::{Address, BlockId, BlockNumber, Bytes, H256};
+use ethers_core::types::{Address, BlockId, BlockNumber, Bytes, TransactionReceipt, H256};
+use ethers_providers::ProviderError;
 use polywrap_provider::provider::{Provider, WrapProvider};
 use polywrap_provider::signer::{Signer, WrapSigner};
-use polywrap_wasm_rs::{BigInt, JSON, BigIntWrapper};
+use polywrap_wasm_rs::{JSON, BigIntWrapper};
 use std::str::FromStr;
 use wrap::module::{Module, ModuleTrait};
 mod wrap;
@@ -10,51 +11,71 @@ use wrap::*;
 
 mod polywrap_provider;
 
-use helpers::{format, mapping};
+use ethers_utils::format;
+use helpers::mapping;
 use polywrap_provider::{error, provider};
+use polywrap_provider::error::{module_method, WrapperError};
 
 mod api;
 mod helpers;
 
 impl ModuleTrait for Module {
     fn get_chain_id(args: wrap::ArgsGetChainId) -> Result<String, String> {
-        let provider = WrapProvider::new(&args.connection);
-        Ok(provider.get_chainid().unwrap().to_string())
+        module_method("getChainId", || {
+            let provider = WrapProvider::new(&args.connection);
+            Ok(provider.get_chainid()?.to_string())
+        })
     }
 
     fn get_balance(args: wrap::ArgsGetBalance) -> Result<BigIntWrapper, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let address = match Address::from_str(&args.address) {
-            Ok(addr) => Ok(addr),
-            Err(e) => Err(format!("Invalid address: {}. Error: {}", &args.address, e)),
-        };
-
-        if address.is_err() {
-            return Err(address.unwrap_err());
-        }
-        let block_tag: BlockId = BlockNumber::Latest.into();
-        let balance = provider.get_balance(address.unwrap(), Some(block_tag));
-
-        if let Err(error) = balance {
-            return Err(format!("Error in get_balance: {}", error.to_string()));
-        }
-        Ok(BigIntWrapper(
-            BigInt::from_str(&balance.unwrap().to_string()).unwrap(),
-        ))
+        module_method("getBalance", || {
+            let provider = WrapProvider::new(&args.connection);
+            let address = mapping::resolve_address(&provider, &args.address)?;
+            let block_tag: BlockId = BlockNumber::Latest.into();
+            let balance = provider.get_balance(address, Some(block_tag))?;
+            Ok(mapping::u256_to_bigintwrapper(&balance))
+        })
     }
 
     fn get_transaction(args: wrap::ArgsGetTransaction) -> Result<TxResponse, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let transaction = provider.get_transaction(H256::from_str(&args.hash).unwrap());
-        if let Ok(tx) = transaction {
-            if let Some(tx) = tx {
-                Ok(mapping::to_wrap_response(&provider, tx))
-            } else {
-                return Err(format!("Transaction with hash {} not found", args.hash));
+        module_method("getTransaction", || {
+            let provider = WrapProvider::new(&args.connection);
+            let hash = parse_tx_hash(&args.hash)?;
+            let tx = provider
+                .get_transaction(hash)?
+                .ok_or_else(|| tx_not_found(&args.hash))?;
+            mapping::to_wrap_response(&provider, tx)
+        })
+    }
+
+    fn get_logs(args: wrap::ArgsGetLogs) -> Result<Vec<wrap::Log>, String> {
+        module_method("getLogs", || {
+            let provider = WrapProvider::new(&args.connection);
+            let filter = mapping::from_wrap_log_filter(args.filter)?;
+            let logs = provider.get_logs(&filter)?;
+            Ok(logs.iter().filter_map(mapping::to_wrap_log).collect())
+        })
+    }
+
+    fn resolve_name(args: wrap::ArgsResolveName) -> Result<String, String> {
+        module_method("resolveName", || {
+            let provider = WrapProvider::new(&args.connection);
+            let address = provider.resolve_name(&args.name)?;
+            Ok(format!("{:#x}", address))
+        })
+    }
+
+    fn lookup_address(args: wrap::ArgsLookupAddress) -> Result<Option<String>, String> {
+        module_method("lookupAddress", || {
+            let provider = WrapProvider::new(&args.connection);
+            let address = Address::from_str(&args.address)
+                .map_err(|e| format!("Invalid address: {}. Error: {}", &args.address, e))?;
+            match provider.lookup_address(address) {
+                Ok(name) => Ok(Some(name)),
+                Err(ProviderError::EnsError(_)) | Err(ProviderError::EnsNotOwned(_)) => Ok(None),
+                Err(e) => Err(e.into()),
             }
-        } else {
-            return Err("Error fetching transaction".to_string());
-        }
+        })
     }
 
     fn check_address(args: wrap::ArgsCheckAddress) -> Result<bool, String> {
@@ -64,290 +85,575 @@ impl ModuleTrait for Module {
         })
     }
 
+    fn get_block_number(args: wrap::ArgsGetBlockNumber) -> Result<BigIntWrapper, String> {
+        module_method("getBlockNumber", || {
+            let provider = WrapProvider::new(&args.connection);
+            let number = provider.get_block_number()?;
+            Ok(mapping::u64_to_bigintwrapper(number))
+        })
+    }
+
+    fn get_block(args: wrap::ArgsGetBlock) -> Result<wrap::Block, String> {
+        module_method("getBlock", || {
+            let provider = WrapProvider::new(&args.connection);
+            let block_id = mapping::from_wrap_block_id(args.block.clone())?;
+            let block = provider
+                .get_block(block_id)?
+                .ok_or_else(|| block_not_found(&args.block))?;
+            mapping::to_wrap_block(&block)
+        })
+    }
+
+    fn get_block_with_transactions(
+        args: wrap::ArgsGetBlockWithTransactions,
+    ) -> Result<wrap::Block, String> {
+        module_method("getBlockWithTransactions", || {
+            let provider = WrapProvider::new(&args.connection);
+            let block_id = mapping::from_wrap_block_id(args.block.clone())?;
+            let block = provider
+                .get_block_with_txs(block_id)?
+                .ok_or_else(|| block_not_found(&args.block))?;
+            // the chain id is only fetched for transactions without one
+            let chain_id = match block.transactions.iter().all(|tx| tx.chain_id.is_some()) {
+                true => Default::default(),
+                false => provider.get_chainid()?,
+            };
+            mapping::to_wrap_block_with_transactions(&block, chain_id)
+        })
+    }
+
     fn get_gas_price(args: wrap::ArgsGetGasPrice) -> Result<BigIntWrapper, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let price = provider.get_gas_price().unwrap();
-        Ok(BigIntWrapper(BigInt::from_str(&price.to_string()).unwrap()))
+        module_method("getGasPrice", || {
+            let provider = WrapProvider::new(&args.connection);
+            let price = provider.get_gas_price()?;
+            Ok(mapping::u256_to_bigintwrapper(&price))
+        })
+    }
+
+    fn get_fee_history(args: wrap::ArgsGetFeeHistory) -> Result<wrap::FeeHistory, String> {
+        module_method("getFeeHistory", || {
+            let provider = WrapProvider::new(&args.connection);
+            let newest_block = match args.newest_block {
+                Some(number) => BlockNumber::Number(
+                    number
+                        .0
+                        .to_string()
+                        .parse::<u64>()
+                        .map_err(|e| format!("Invalid newest block: {}", e))?
+                        .into(),
+                ),
+                None => BlockNumber::Latest,
+            };
+            let reward_percentiles = args
+                .reward_percentiles
+                .map(|percentiles| mapping::from_wrap_percentiles(&percentiles))
+                .transpose()?
+                .unwrap_or_default();
+            let fee_history =
+                provider.fee_history(args.block_count, newest_block, &reward_percentiles)?;
+            Ok(mapping::to_wrap_fee_history(&fee_history))
+        })
+    }
+
+    fn get_gas_market_summary(
+        args: wrap::ArgsGetGasMarketSummary,
+    ) -> Result<wrap::GasMarketSummary, String> {
+        module_method("getGasMarketSummary", || {
+            let provider = WrapProvider::new(&args.connection);
+            let block_count = args.block_count.unwrap_or(10);
+            let reward_percentiles = args
+                .reward_percentiles
+                .map(|percentiles| mapping::from_wrap_percentiles(&percentiles))
+                .transpose()?
+                .unwrap_or_else(|| vec![10.0, 50.0, 90.0]);
+            let fee_history =
+                provider.fee_history(block_count, BlockNumber::Latest, &reward_percentiles)?;
+            let summary = ethers_utils::summarize_fee_history(&fee_history, &reward_percentiles)
+                .ok_or_else(|| {
+                    WrapperError::NotFound("Base fees not found: EIP-1559 is not activated".into())
+                })?;
+            Ok(mapping::to_wrap_gas_market_summary(&summary))
+        })
     }
 
     fn estimate_eip1559_fees(
         args: wrap::ArgsEstimateEip1559Fees,
     ) -> Result<wrap::Eip1559FeesEstimate, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let price = provider.estimate_eip1559_fees(None).unwrap();
-        Ok(wrap::Eip1559FeesEstimate {
-            max_fee_per_gas: BigIntWrapper(BigInt::from_str(&price.0.to_string()).unwrap()),
-            max_priority_fee_per_gas: BigIntWrapper(
-                BigInt::from_str(&price.1.to_string()).unwrap(),
-            ),
+        module_method("estimateEip1559Fees", || {
+            let fee_policy = args.fee_policy.map(mapping::from_wrap_fee_policy).transpose()?;
+            let provider = WrapProvider::new(&args.connection).with_fee_policy(fee_policy);
+            let (max_fee_per_gas, max_priority_fee_per_gas) =
+                provider.estimate_eip1559_fees(None)?;
+            Ok(wrap::Eip1559FeesEstimate {
+                max_fee_per_gas: mapping::u256_to_bigintwrapper(&max_fee_per_gas),
+                max_priority_fee_per_gas: mapping::u256_to_bigintwrapper(
+                    &max_priority_fee_per_gas,
+                ),
+            })
         })
     }
 
     fn get_signer_address(args: wrap::ArgsGetSignerAddress) -> Result<String, String> {
-        let address = WrapSigner::new(&args.connection).address();
-        Ok(format!("{:#x}", address))
+        module_method("getSignerAddress", || {
+            let address = WrapSigner::new(&args.connection)?.address();
+            Ok(format!("{:#x}", address))
+        })
     }
 
     fn get_signer_balance(args: wrap::ArgsGetSignerBalance) -> Result<BigIntWrapper, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let address = WrapSigner::new(&args.connection).address();
-        let block_tag: BlockId = BlockNumber::Latest.into();
-        let balance = provider.get_balance(address, Some(block_tag)).unwrap();
-        Ok(BigIntWrapper(
-            BigInt::from_str(&balance.to_string()).unwrap(),
-        ))
+        module_method("getSignerBalance", || {
+            let provider = WrapProvider::new(&args.connection);
+            let address = WrapSigner::new(&args.connection)?.address();
+            let block_tag: BlockId = BlockNumber::Latest.into();
+            let balance = provider.get_balance(address, Some(block_tag))?;
+            Ok(mapping::u256_to_bigintwrapper(&balance))
+        })
     }
 
     fn get_signer_transaction_count(
         args: wrap::ArgsGetSignerTransactionCount,
     ) -> Result<BigIntWrapper, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let address = WrapSigner::new(&args.connection).address();
-        let block_tag: BlockId = BlockNumber::Latest.into();
-        let count = provider
-            .get_transaction_count(address, Some(block_tag))
-            .unwrap();
-        Ok(BigIntWrapper(BigInt::from_str(&count.to_string()).unwrap()))
+        module_method("getSignerTransactionCount", || {
+            let provider = WrapProvider::new(&args.connection);
+            let address = WrapSigner::new(&args.connection)?.address();
+            let block_tag: BlockId = BlockNumber::Latest.into();
+            let count = provider.get_transaction_count(address, Some(block_tag))?;
+            Ok(mapping::u256_to_bigintwrapper(&count))
+        })
     }
 
     fn sign_message(args: wrap::ArgsSignMessage) -> Result<String, String> {
-        let signer = WrapSigner::new(&args.connection);
-        let signature = signer.sign_message(&args.message).unwrap();
-        let bytes: Bytes = signature.to_vec().into();
-        Ok(format!("{}", bytes).to_string())
+        module_method("signMessage", || {
+            let signer = WrapSigner::new(&args.connection)?;
+            let signature = signer.sign_message(&args.message)?;
+            let bytes: Bytes = signature.to_vec().into();
+            Ok(format!("{}", bytes))
+        })
     }
 
     fn sign_message_bytes(args: wrap::ArgsSignMessageBytes) -> Result<String, String> {
-        let signer = WrapSigner::new(&args.connection);
-        let signature = signer.sign_message(&args.bytes).unwrap();
-        let bytes: Bytes = signature.to_vec().into();
-        Ok(format!("{}", bytes).to_string())
+        module_method("signMessageBytes", || {
+            let signer = WrapSigner::new(&args.connection)?;
+            let signature = signer.sign_message(&args.bytes)?;
+            let bytes: Bytes = signature.to_vec().into();
+            Ok(format!("{}", bytes))
+        })
     }
 
     fn sign_transaction(args: wrap::ArgsSignTransaction) -> Result<String, String> {
-        let signer = WrapSigner::new(&args.connection);
-        let tx = mapping::from_wrap_request(args.tx);
-        let signature = signer.sign_transaction(&tx).unwrap();
-        let bytes: Bytes = signature.to_vec().into();
-        Ok(format!("{}", bytes).to_string())
+        module_method("signTransaction", || {
+            let provider = WrapProvider::new(&args.connection);
+            let signer = WrapSigner::new(&args.connection)?;
+            let mut tx = mapping::from_wrap_request(args.tx)?;
+            provider.resolve_transaction_to(&mut tx)?;
+            let signature = signer.sign_transaction(&tx)?;
+            let bytes: Bytes = signature.to_vec().into();
+            Ok(format!("{}", bytes))
+        })
     }
 
     fn sign_typed_data(args: wrap::ArgsSignTypedData) -> Result<String, String> {
-        let address = WrapSigner::new(&args.connection).address();
-        let address_value = JSON::Value::String(format!("{:#x}", address));
-        let params = JSON::Value::Array(vec![address_value, args.payload.into()]);
-        let provider = WrapProvider::new(&args.connection);
-        provider
-            .request("eth_signTypedData_v4", params)
-            .map_err(|e| format!("Error in sign_typed_data method: {}", e.to_string()))
+        module_method("signTypedData", || {
+            let signer = WrapSigner::new(&args.connection)?;
+            if let Some(local) = signer.local() {
+                let typed_data = ethers_utils::parse_typed_data(&args.payload)
+                    .map_err(|e| format!("Invalid typed data: {}", e))?;
+                let signature = local.sign_typed_data(&typed_data)?;
+                let bytes: Bytes = signature.to_vec().into();
+                return Ok(format!("{}", bytes));
+            }
+            // the wallet plugin can't sign a digest without prefixing it, so the payload is
+            // forwarded as given for the wallet to hash and sign
+            let address = signer.address();
+            let address_value = JSON::Value::String(format!("{:#x}", address));
+            let params = JSON::Value::Array(vec![address_value, args.payload.into()]);
+            let provider = WrapProvider::new(&args.connection);
+            Ok(provider.request("eth_signTypedData_v4", params)?)
+        })
+    }
+
+    fn create_access_list(
+        args: wrap::ArgsCreateAccessList,
+    ) -> Result<Vec<wrap::AccessItem>, String> {
+        module_method("createAccessList", || {
+            let provider = WrapProvider::new(&args.connection);
+            let mut tx = mapping::from_wrap_request(args.tx)?;
+            if tx.from().is_none() {
+                let signer = WrapSigner::new(&args.connection)?;
+                tx.set_from(signer.address());
+            }
+            provider.resolve_transaction_to(&mut tx)?;
+            let access_list = provider.create_access_list(&tx, None)?;
+            Ok(mapping::to_wrap_access_list(&access_list.access_list))
+        })
     }
 
     fn send_rpc(args: wrap::ArgsSendRpc) -> Result<String, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let res: serde_json::Value = provider.request(&args.method, args.params).unwrap();
-        Ok(res.to_string())
+        module_method("sendRpc", || {
+            let provider = WrapProvider::new(&args.connection);
+            let res: serde_json::Value = provider.request(&args.method, args.params)?;
+            Ok(res.to_string())
+        })
     }
 
     fn estimate_transaction_gas(
         args: wrap::ArgsEstimateTransactionGas,
     ) -> Result<BigIntWrapper, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let tx = mapping::from_wrap_request(args.tx);
-        let gas = provider.estimate_gas(&tx, None).unwrap();
-        Ok(BigIntWrapper(BigInt::from_str(&gas.to_string()).unwrap()))
+        module_method("estimateTransactionGas", || {
+            let provider = WrapProvider::new(&args.connection);
+            let mut tx = mapping::from_wrap_request(args.tx)?;
+            provider.resolve_transaction_to(&mut tx)?;
+            let gas = provider.estimate_gas(&tx, None)?;
+            Ok(mapping::u256_to_bigintwrapper(&gas))
+        })
+    }
+
+    fn trace_call(args: wrap::ArgsTraceCall) -> Result<wrap::CallFrame, String> {
+        module_method("traceCall", || {
+            let provider = WrapProvider::new(&args.connection);
+            let mut tx = mapping::from_wrap_request(args.tx)?;
+            let abi = parse_abi(args.abi)?;
+            let block = match args.block {
+                Some(number) => BlockNumber::Number(
+                    number
+                        .0
+                        .to_string()
+                        .parse::<u64>()
+                        .map_err(|e| format!("Invalid block: {}", e))?
+                        .into(),
+                ),
+                None => BlockNumber::Latest,
+            };
+            let tracer = mapping::from_wrap_call_tracer_options(args.tracer);
+
+            provider.resolve_transaction_to(&mut tx)?;
+            let frame = provider.trace_call(&tx, Some(block.into()), &tracer)?;
+            Ok(mapping::to_wrap_call_frame(&frame, abi.as_ref()))
+        })
+    }
+
+    fn trace_transaction(args: wrap::ArgsTraceTransaction) -> Result<wrap::CallFrame, String> {
+        module_method("traceTransaction", || {
+            let provider = WrapProvider::new(&args.connection);
+            let tx_hash = parse_tx_hash(&args.tx_hash)?;
+            let abi = parse_abi(args.abi)?;
+            let tracer = mapping::from_wrap_call_tracer_options(args.tracer);
+
+            let frame = provider.trace_transaction(tx_hash, &tracer)?;
+            Ok(mapping::to_wrap_call_frame(&frame, abi.as_ref()))
+        })
     }
 
     fn await_transaction(args: wrap::ArgsAwaitTransaction) -> Result<wrap::TxReceipt, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let tx_hash = H256::from_str(&args.tx_hash).unwrap();
-        provider
-            .await_transaction(tx_hash.clone(), args.confirmations, args.timeout)
-            .unwrap();
-        let receipt = provider.get_transaction_receipt(tx_hash).unwrap().unwrap();
-        let tx_receipt = mapping::to_wrap_receipt(receipt, args.confirmations);
-        Ok(tx_receipt)
+        module_method("awaitTransaction", || {
+            let provider = WrapProvider::new(&args.connection);
+            let tx_hash = parse_tx_hash(&args.tx_hash)?;
+            let receipt =
+                wait_for_receipt(&provider, tx_hash, args.confirmations, args.timeout)?;
+            mapping::to_wrap_receipt(receipt, args.confirmations)
+        })
     }
 
     fn send_transaction(args: wrap::ArgsSendTransaction) -> Result<wrap::TxResponse, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
+        module_method("sendTransaction", || {
+            let provider = WrapProvider::new(&args.connection);
+            let signer = WrapSigner::new(&args.connection)?;
 
-        let mut tx = mapping::from_wrap_request(args.tx);
+            let mut tx = mapping::from_wrap_request(args.tx)?;
 
-        let tx_hash = api::send_transaction(&provider, &signer, &mut tx);
-        let response = provider.get_transaction(tx_hash).unwrap().unwrap();
-        let tx_response = mapping::to_wrap_response(&provider, response);
-        Ok(tx_response)
+            let tx_hash = api::send_transaction(&provider, &signer, &mut tx)?;
+            get_sent_transaction(&provider, tx_hash)
+        })
+    }
+
+    fn send_transactions(
+        args: wrap::ArgsSendTransactions,
+    ) -> Result<wrap::SendTransactionsResult, String> {
+        module_method("sendTransactions", || {
+            let provider = WrapProvider::new(&args.connection);
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let mut txs = args
+                .txs
+                .into_iter()
+                .map(mapping::from_wrap_request)
+                .collect::<Result<Vec<_>, String>>()?;
+
+            let (hashes, failure) = api::send_transactions(&provider, &signer, &mut txs);
+            Ok(wrap::SendTransactionsResult {
+                hashes: hashes.iter().map(|hash| format!("{:#x}", hash)).collect(),
+                failed_index: failure.as_ref().map(|(index, _)| *index as u32),
+                error: failure.map(|(_, error)| {
+                    WrapperError::from(error).to_module_error("sendTransactions")
+                }),
+            })
+        })
+    }
+
+    fn speed_up_transaction(
+        args: wrap::ArgsSpeedUpTransaction,
+    ) -> Result<wrap::TxResponse, String> {
+        let fee_bump = args
+            .fee_bump
+            .map(u64::from)
+            .unwrap_or(ethers_utils::MIN_REPLACEMENT_FEE_BUMP);
+        module_method("speedUpTransaction", || {
+            replace_transaction(&args.tx_hash, fee_bump, false, &args.connection)
+        })
+    }
+
+    fn cancel_transaction(args: wrap::ArgsCancelTransaction) -> Result<wrap::TxResponse, String> {
+        let fee_bump = ethers_utils::MIN_REPLACEMENT_FEE_BUMP;
+        module_method("cancelTransaction", || {
+            replace_transaction(&args.tx_hash, fee_bump, true, &args.connection)
+        })
     }
 
     fn send_transaction_and_wait(
         args: wrap::ArgsSendTransactionAndWait,
     ) -> Result<wrap::TxReceipt, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
-
-        let mut tx = mapping::from_wrap_request(args.tx);
-
-        let tx_hash = api::send_transaction(&provider, &signer, &mut tx);
-        provider
-            .await_transaction(tx_hash.clone(), 1, None)
-            .unwrap();
-        let receipt = provider.get_transaction_receipt(tx_hash).unwrap().unwrap();
-        let tx_receipt = mapping::to_wrap_receipt(receipt, 1);
-        Ok(tx_receipt)
+        module_method("sendTransactionAndWait", || {
+            let provider = WrapProvider::new(&args.connection);
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let mut tx = mapping::from_wrap_request(args.tx)?;
+            let abi = parse_abi(args.abi)?;
+
+            let tx_hash = api::send_transaction(&provider, &signer, &mut tx)?;
+            let receipt = wait_for_receipt(&provider, tx_hash, 1, None)?;
+            let mut tx_receipt = mapping::to_wrap_receipt(receipt, 1)?;
+            if let Some(abi) = abi {
+                tx_receipt.decoded_logs =
+                    Some(mapping::to_wrap_decoded_logs(&abi, &tx_receipt.logs)?);
+            }
+            Ok(tx_receipt)
+        })
     }
 
     fn deploy_contract(args: wrap::ArgsDeployContract) -> Result<String, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
-
-        let abi: Abi = serde_json::from_str(&args.abi).unwrap();
-        let bytecode = Bytes::from_str(&args.bytecode).unwrap();
-        let params: Vec<String> = args.args.unwrap_or(vec![]);
-        let tx_options: mapping::EthersTxOptions = mapping::from_wrap_tx_options(args.options);
-
-        let mut tx =
-            api::create_deploy_contract_transaction(&abi, bytecode, &params, &tx_options).unwrap();
+        module_method("deployContract", || {
+            let tx_options = mapping::from_wrap_tx_options(args.options)?;
+            let provider = WrapProvider::new(&args.connection)
+                .with_fee_policy(tx_options.fee_policy.clone());
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let abi: Abi = serde_json::from_str(&args.abi)
+                .map_err(|e| format!("Invalid ABI: {}", e))?;
+            let bytecode = Bytes::from_str(&args.bytecode)
+                .map_err(|e| format!("Invalid bytecode: {}", e))?;
+            let params: Vec<String> = args.args.unwrap_or(vec![]);
+
+            let mut tx =
+                api::create_deploy_contract_transaction(&abi, bytecode, &params, &tx_options)?;
+            if tx_options.auto_access_list {
+                api::attach_access_list(&provider, &signer, &mut tx)?;
+            }
 
-        let tx_hash = api::send_transaction(&provider, &signer, &mut tx);
-        provider
-            .await_transaction(tx_hash.clone(), 1, None)
-            .unwrap();
-        let receipt = provider.get_transaction_receipt(tx_hash).unwrap().unwrap();
-        let address = receipt
-            .contract_address
-            .expect("Contract failed to deploy.");
-        Ok(format!("{:#x}", address))
+            let tx_hash = api::send_transaction(&provider, &signer, &mut tx)?;
+            let receipt = wait_for_receipt(&provider, tx_hash, 1, None)?;
+            let address = receipt.contract_address.ok_or_else(|| {
+                WrapperError::NotFound(format!(
+                    "Contract address not found in the receipt of transaction {:#x}",
+                    tx_hash
+                ))
+            })?;
+            Ok(format!("{:#x}", address))
+        })
     }
 
     fn estimate_contract_call_gas(
         args: wrap::ArgsEstimateContractCallGas,
     ) -> Result<BigIntWrapper, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
-
-        let address = match Address::from_str(&args.address) {
-            Ok(addr) => addr,
-            Err(e) => panic!("Invalid contract address: {}. Error: {}", &args.address, e),
-        };
-        let params: Vec<String> = args.args.unwrap_or(vec![]);
-        let tx_options: mapping::EthersTxOptions = mapping::from_wrap_tx_options(args.options);
-
-        let gas = api::estimate_contract_call_gas(
-            &provider,
-            &signer,
-            address,
-            &args.method,
-            &params,
-            &tx_options,
-        );
-        {
-            let this = BigInt::from_str(&gas.to_string());
-            match this {
-                Ok(t) => Ok(BigIntWrapper(t)),
-                Err(e) => Err((|e: polywrap_wasm_rs::DecodeError| e.to_string())(e.into())),
-            }
-        }
+        module_method("estimateContractCallGas", || {
+            let tx_options = mapping::from_wrap_tx_options(args.options)?;
+            let provider = WrapProvider::new(&args.connection)
+                .with_fee_policy(tx_options.fee_policy.clone());
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let address = mapping::resolve_address(&provider, &args.address)?;
+            let params: Vec<String> = args.args.unwrap_or(vec![]);
+
+            let gas = api::estimate_contract_call_gas(
+                &provider,
+                &signer,
+                address,
+                &args.method,
+                &params,
+                &tx_options,
+            )?;
+            Ok(mapping::u256_to_bigintwrapper(&gas))
+        })
     }
 
     fn call_contract_view(args: wrap::ArgsCallContractView) -> Result<String, String> {
-        let provider = WrapProvider::new(&args.connection);
+        module_method("callContractView", || {
+            let provider = WrapProvider::new(&args.connection);
+
+            let address = mapping::resolve_address(&provider, &args.address)?;
+            let params: Vec<String> = args.args.unwrap_or(vec![]);
 
-        let address = match Address::from_str(&args.address) {
-            Ok(addr) => addr,
-            Err(e) => panic!("Invalid contract address: {}. Error: {}", &args.address, e),
-        };
-        let params: Vec<String> = args.args.unwrap_or(vec![]);
+            let overrides =
+                mapping::from_wrap_call_overrides(args.state_overrides, args.block_overrides)?;
 
-        let tokens = api::call_contract_view(&provider, address, &args.method, &params);
-        Ok(format::format_tokens(&tokens))
+            let tokens =
+                api::call_contract_view(&provider, address, &args.method, &params, &overrides)?;
+            if args.named_output.unwrap_or(false) {
+                let names = ethers_utils::parse_output_names(&args.method)?;
+                Ok(format::format_named_tokens(&tokens, &names))
+            } else {
+                Ok(format::format_tokens(&tokens))
+            }
+        })
+    }
+
+    fn call_contract_views(
+        args: wrap::ArgsCallContractViews,
+    ) -> Result<Vec<wrap::ContractCallResult>, String> {
+        module_method("callContractViews", || {
+            let provider = WrapProvider::new(&args.connection);
+
+            let multicall = match args.multicall_address {
+                Some(address) => Some(mapping::resolve_address(&provider, &address)?),
+                None => None,
+            };
+            let calls = args
+                .calls
+                .into_iter()
+                .map(|call| {
+                    let address = mapping::resolve_address(&provider, &call.address)?;
+                    let params: Vec<String> = call.args.unwrap_or(vec![]);
+                    let (function, data) = ethers_utils::encode_function(&call.method, &params)?;
+                    Ok(api::ViewCall {
+                        address,
+                        function,
+                        data,
+                        allow_failure: call.allow_failure.unwrap_or(false),
+                    })
+                })
+                .collect::<Result<Vec<api::ViewCall>, WrapperError>>()?;
+
+            let results = api::call_contract_views(&provider, multicall, &calls)?;
+            Ok(results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(tokens) => wrap::ContractCallResult {
+                        success: true,
+                        result: format::format_tokens(&tokens),
+                        error: None,
+                    },
+                    Err(error) => wrap::ContractCallResult {
+                        success: false,
+                        result: "".to_string(),
+                        error: Some(error),
+                    },
+                })
+                .collect())
+        })
     }
 
     fn call_contract_static(args: ArgsCallContractStatic) -> Result<wrap::StaticTxResult, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
-
-        let address = match Address::from_str(&args.address) {
-            Ok(addr) => addr,
-            Err(e) => panic!("Invalid contract address: {}. Error: {}", &args.address, e),
-        };
-        let params: Vec<String> = args.args.unwrap_or(vec![]);
-        let tx_options: mapping::EthersTxOptions = mapping::from_wrap_tx_options(args.options);
-
-        let result = api::call_contract_static(
-            &provider,
-            &signer,
-            address,
-            &args.method,
-            &params,
-            &tx_options,
-        );
-        Ok(match result {
-            Ok(tokens) => wrap::StaticTxResult {
-                result: format::format_tokens(&tokens),
-                error: false,
-            },
-            Err(e) => wrap::StaticTxResult {
-                result: e.to_string(),
-                error: true,
-            },
+        module_method("callContractStatic", || {
+            let tx_options = mapping::from_wrap_tx_options(args.options)?;
+            let provider = WrapProvider::new(&args.connection)
+                .with_fee_policy(tx_options.fee_policy.clone());
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let address = mapping::resolve_address(&provider, &args.address)?;
+            let params: Vec<String> = args.args.unwrap_or(vec![]);
+
+            let abi = parse_abi(args.abi)?;
+
+            let overrides =
+                mapping::from_wrap_call_overrides(args.state_overrides, args.block_overrides)?;
+
+            let result = api::call_contract_static(
+                &provider,
+                &signer,
+                address,
+                &args.method,
+                &params,
+                &tx_options,
+                &overrides,
+            );
+            Ok(match result {
+                Ok(tokens) => wrap::StaticTxResult {
+                    result: format::format_tokens(&tokens),
+                    error: false,
+                    call_error: None,
+                },
+                Err(e) => {
+                    let call_error = mapping::to_wrap_call_error(&e, abi.as_ref());
+                    wrap::StaticTxResult {
+                        result: e.to_string(),
+                        error: true,
+                        call_error: Some(call_error),
+                    }
+                }
+            })
         })
     }
 
     fn call_contract_method(
         args: wrap::ArgsCallContractMethod,
     ) -> Result<wrap::TxResponse, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
-
-        let address = match Address::from_str(&args.address) {
-            Ok(addr) => addr,
-            Err(e) => panic!("Invalid contract address: {}. Error: {}", &args.address, e),
-        };
-        let params: Vec<String> = args.args.unwrap_or(vec![]);
-        let tx_options: mapping::EthersTxOptions = mapping::from_wrap_tx_options(args.options);
-
-        let tx_hash = api::call_contract_method(
-            &provider,
-            &signer,
-            address,
-            &args.method,
-            &params,
-            &tx_options,
-        );
-
-        let response = provider.get_transaction(tx_hash).unwrap().unwrap();
-        let tx_response = mapping::to_wrap_response(&provider, response);
-        Ok(tx_response)
+        module_method("callContractMethod", || {
+            let tx_options = mapping::from_wrap_tx_options(args.options)?;
+            let provider = WrapProvider::new(&args.connection)
+                .with_fee_policy(tx_options.fee_policy.clone());
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let address = mapping::resolve_address(&provider, &args.address)?;
+            let params: Vec<String> = args.args.unwrap_or(vec![]);
+
+            let tx_hash = api::call_contract_method(
+                &provider,
+                &signer,
+                address,
+                &args.method,
+                &params,
+                &tx_options,
+            )?;
+            get_sent_transaction(&provider, tx_hash)
+        })
     }
 
     fn call_contract_method_and_wait(
         args: wrap::ArgsCallContractMethodAndWait,
     ) -> Result<wrap::TxReceipt, String> {
-        let provider = WrapProvider::new(&args.connection);
-        let signer = WrapSigner::new(&args.connection);
-
-        let address = match Address::from_str(&args.address) {
-            Ok(addr) => addr,
-            Err(e) => panic!("Invalid contract address: {}. Error: {}", &args.address, e),
-        };
-        let params: Vec<String> = args.args.unwrap_or(vec![]);
-        let tx_options: mapping::EthersTxOptions = mapping::from_wrap_tx_options(args.options);
-
-        let tx_hash = api::call_contract_method(
-            &provider,
-            &signer,
-            address,
-            &args.method,
-            &params,
-            &tx_options,
-        );
-        provider
-            .await_transaction(tx_hash.clone(), 1, None)
-            .unwrap();
-        let receipt = provider.get_transaction_receipt(tx_hash).unwrap().unwrap();
-        let tx_receipt = mapping::to_wrap_receipt(receipt, 1);
-        Ok(tx_receipt)
+        module_method("callContractMethodAndWait", || {
+            let tx_options = mapping::from_wrap_tx_options(args.options)?;
+            let provider = WrapProvider::new(&args.connection)
+                .with_fee_policy(tx_options.fee_policy.clone());
+            let signer = WrapSigner::new(&args.connection)?;
+
+            let address = mapping::resolve_address(&provider, &args.address)?;
+            let params: Vec<String> = args.args.unwrap_or(vec![]);
+            let abi = parse_abi(args.abi)?;
+
+            let tx_hash = api::call_contract_method(
+                &provider,
+                &signer,
+                address,
+                &args.method,
+                &params,
+                &tx_options,
+            )?;
+            let receipt = wait_for_receipt(&provider, tx_hash, 1, None)?;
+            let mut tx_receipt = mapping::to_wrap_receipt(receipt, 1)?;
+            if let Some(abi) = abi {
+                tx_receipt.decoded_logs =
+                    Some(mapping::to_wrap_decoded_logs(&abi, &tx_receipt.logs)?);
+            }
+            Ok(tx_receipt)
+        })
+    }
+
+    fn decode_logs(args: wrap::ArgsDecodeLogs) -> Result<Vec<wrap::DecodedLog>, String> {
+        module_method("decodeLogs", || {
+            let abi: Abi = serde_json::from_str(&args.abi)
+                .map_err(|e| format!("Invalid ABI: {}", e))?;
+            mapping::to_wrap_decoded_logs(&abi, &args.logs)
+        })
     }
 
     // Re-export utils
@@ -355,6 +661,39 @@ impl ModuleTrait for Module {
         UtilsModule::keccak256(&imported::utils_module::ArgsKeccak256 { value: args.value })
     }
 
+    fn hash_typed_data(args: ArgsHashTypedData) -> Result<String, String> {
+        UtilsModule::hash_typed_data(&imported::utils_module::ArgsHashTypedData {
+            payload: args.payload,
+        })
+    }
+
+    fn recover_address(args: ArgsRecoverAddress) -> Result<String, String> {
+        UtilsModule::recover_address(&imported::utils_module::ArgsRecoverAddress {
+            digest: args.digest,
+            signature: args.signature,
+        })
+    }
+
+    fn verify_message(args: ArgsVerifyMessage) -> Result<String, String> {
+        UtilsModule::verify_message(&imported::utils_module::ArgsVerifyMessage {
+            message: args.message,
+            signature: args.signature,
+        })
+    }
+
+    fn verify_typed_data(args: ArgsVerifyTypedData) -> Result<String, String> {
+        UtilsModule::verify_typed_data(&imported::utils_module::ArgsVerifyTypedData {
+            payload: args.payload,
+            signature: args.signature,
+        })
+    }
+
+    fn recover_transaction_signer(args: ArgsRecoverTransactionSigner) -> Result<String, String> {
+        UtilsModule::recover_transaction_signer(
+            &imported::utils_module::ArgsRecoverTransactionSigner { raw_tx: args.raw_tx },
+        )
+    }
+
     fn keccak256_bytes_encode_packed(
         args: ArgsKeccak256BytesEncodePacked,
     ) -> Result<String, String> {
@@ -394,6 +733,27 @@ impl ModuleTrait for Module {
         })
     }
 
+    fn decode_function_data(args: ArgsDecodeFunctionData) -> Result<String, String> {
+        UtilsModule::decode_function_data(&imported::utils_module::ArgsDecodeFunctionData {
+            method: args.method,
+            data: args.data,
+        })
+    }
+
+    fn decode_function_result(args: ArgsDecodeFunctionResult) -> Result<String, String> {
+        UtilsModule::decode_function_result(&imported::utils_module::ArgsDecodeFunctionResult {
+            method: args.method,
+            data: args.data,
+        })
+    }
+
+    fn decode_params(args: ArgsDecodeParams) -> Result<String, String> {
+        UtilsModule::decode_params(&imported::utils_module::ArgsDecodeParams {
+            types: args.types,
+            data: args.data,
+        })
+    }
+
     fn to_wei(args: ArgsToWei) -> Result<String, String> {
         UtilsModule::to_wei(&imported::utils_module::ArgsToWei { eth: args.eth })
     }
@@ -408,4 +768,103 @@ impl ModuleTrait for Module {
             values: args.values,
         })
     }
+
+    fn encode_event_topics(args: ArgsEncodeEventTopics) -> Result<Vec<Option<String>>, String> {
+        UtilsModule::encode_event_topics(&imported::utils_module::ArgsEncodeEventTopics {
+            event: args.event,
+            values: args.values,
+        })
+    }
+}
+
+/// Replaces a pending transaction of the signer, sent again or cancelled with bumped fees
+fn replace_transaction(
+    tx_hash: &str,
+    fee_bump: u64,
+    cancel: bool,
+    connection: &Option<Connection>,
+) -> Result<wrap::TxResponse, WrapperError> {
+    if fee_bump < ethers_utils::MIN_REPLACEMENT_FEE_BUMP {
+        return Err(WrapperError::InvalidInput(format!(
+            "Fee bump must be at least {}%",
+            ethers_utils::MIN_REPLACEMENT_FEE_BUMP
+        )));
+    }
+    let provider = WrapProvider::new(connection);
+    let signer = WrapSigner::new(connection)?;
+
+    let hash = parse_tx_hash(tx_hash)?;
+    let original = provider
+        .get_transaction(hash)?
+        .ok_or_else(|| tx_not_found(tx_hash))?;
+    if original.block_number.is_some() {
+        let error = format!("Transaction with hash {} is already mined", tx_hash);
+        return Err(WrapperError::InvalidInput(error));
+    }
+    if original.from != signer.address() {
+        let error = format!("Transaction with hash {} was not sent by the signer", tx_hash);
+        return Err(WrapperError::InvalidInput(error));
+    }
+
+    let mut tx = api::create_replacement_transaction(&provider, &original, fee_bump, cancel)?;
+    let tx_hash = api::send_transaction(&provider, &signer, &mut tx)?;
+    get_sent_transaction(&provider, tx_hash)
+}
+
+/// Gets a transaction just sent, which the node knows about even while it's pending
+fn get_sent_transaction(
+    provider: &WrapProvider,
+    tx_hash: H256,
+) -> Result<wrap::TxResponse, WrapperError> {
+    let response = provider
+        .get_transaction(tx_hash)?
+        .ok_or_else(|| tx_not_found(&format!("{:#x}", tx_hash)))?;
+    mapping::to_wrap_response(provider, response)
+}
+
+/// Waits for the confirmations of a transaction, and gets its receipt
+fn wait_for_receipt(
+    provider: &WrapProvider,
+    tx_hash: H256,
+    confirmations: u32,
+    timeout: Option<u32>,
+) -> Result<TransactionReceipt, WrapperError> {
+    provider
+        .await_transaction(tx_hash, confirmations, timeout)
+        .map_err(|e| {
+            // the provider plugin fails with a plain message when the timeout expires
+            let message = e.to_string();
+            let lowercase = message.to_lowercase();
+            if lowercase.contains("timeout") || lowercase.contains("timed out") {
+                WrapperError::Timeout(message)
+            } else {
+                e.into()
+            }
+        })?;
+    provider.get_transaction_receipt(tx_hash)?.ok_or_else(|| {
+        WrapperError::NotFound(format!("Receipt of transaction {:#x} not found", tx_hash))
+    })
+}
+
+fn parse_tx_hash(hash: &str) -> Result<H256, WrapperError> {
+    H256::from_str(hash)
+        .map_err(|e| WrapperError::InvalidInput(format!("Invalid transaction hash: {}", e)))
+}
+
+fn parse_abi(abi: Option<String>) -> Result<Option<Abi>, WrapperError> {
+    match abi {
+        Some(abi) => Ok(Some(
+            serde_json::from_str(&abi).map_err(|e| format!("Invalid ABI: {}", e))?,
+        )),
+        None => Ok(None),
+    }
+}
+
+fn tx_not_found(hash: &str) -> WrapperError {
+    WrapperError::NotFound(format!("Transaction with hash {} not found", hash))
+}
+
+fn block_not_found(block: &Option<String>) -> WrapperError {
+    let block = block.as_deref().unwrap_or("latest");
+    WrapperError::NotFound(format!("Block {} not found", block))
 }
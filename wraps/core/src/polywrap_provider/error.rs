@@ -1,7 +1,44 @@
+use std::fmt;
+
+use ethers_providers::{JsonRpcError, ProviderError, RpcError};
+use ethers_utils::RpcErrorReason;
+use serde_json::json;
 use thiserror::Error;
 
+use super::signer::SignerError;
+
+/// Kind of a `WrapperError`, which callers of the Module can switch on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Invalid argument, like a malformed address, hash or ABI
+    InvalidInput,
+    /// Values which couldn't be ABI encoded or decoded
+    Abi,
+    /// Request which didn't reach the node, or whose response couldn't be read
+    Transport,
+    /// Error response of the node, with its code and data
+    JsonRpc,
+    /// Call or transaction reverted by the EVM
+    Reverted,
+    /// Transaction not confirmed in time
+    Timeout,
+    /// Transaction, receipt, block or ENS name not found
+    NotFound,
+    /// Signer which isn't available or refused to sign
+    SignerRejected,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum WrapperError {
+    /// Error type from invalid arguments of a Module method
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
     #[error("Utils Encoder Error: {0:?}")]
     EncodeError(ethers_utils::EncodeError),
     #[error("Provider Error: {0:?}")]
@@ -9,6 +46,113 @@ pub enum WrapperError {
     /// Error type from abi parsing
     #[error("ContractError Error: {0:?}")]
     ContractError(String),
+    #[error("Signer Error: {0}")]
+    SignerError(SignerError),
+    /// Error type from multicall batches with a reverted call which isn't allowed to fail
+    #[error("Reverted: {0}")]
+    Reverted(String),
+    /// Error type from transactions not confirmed before the timeout
+    #[error("Timeout: {0}")]
+    Timeout(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+}
+
+impl WrapperError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            WrapperError::InvalidInput(_) => ErrorKind::InvalidInput,
+            WrapperError::EncodeError(ethers_utils::EncodeError::InvalidInput(_)) => {
+                ErrorKind::InvalidInput
+            }
+            WrapperError::EncodeError(_) => ErrorKind::Abi,
+            WrapperError::ProviderError(e) => provider_error_kind(e),
+            WrapperError::ContractError(_) => ErrorKind::Abi,
+            WrapperError::SignerError(
+                SignerError::InvalidPrivateKey(_) | SignerError::InvalidSender(_),
+            ) => ErrorKind::InvalidInput,
+            WrapperError::SignerError(_) => ErrorKind::SignerRejected,
+            WrapperError::Reverted(_) => ErrorKind::Reverted,
+            WrapperError::Timeout(_) => ErrorKind::Timeout,
+            WrapperError::NotFound(_) => ErrorKind::NotFound,
+        }
+    }
+
+    /// Message of the error, without the name of its variant
+    pub fn message(&self) -> String {
+        match self {
+            WrapperError::InvalidInput(message)
+            | WrapperError::ContractError(message)
+            | WrapperError::Reverted(message)
+            | WrapperError::Timeout(message)
+            | WrapperError::NotFound(message) => message.clone(),
+            WrapperError::EncodeError(e) => e.to_string(),
+            WrapperError::ProviderError(e) => e.to_string(),
+            WrapperError::SignerError(e) => e.to_string(),
+        }
+    }
+
+    /// JSON-RPC error response of the node, if the error is one
+    pub fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            WrapperError::ProviderError(e) => e.as_error_response(),
+            _ => None,
+        }
+    }
+
+    /// Renders the error as returned by the Module methods, a JSON object with the kind of
+    /// the error, the method which failed, a message, and the code, data and reason of
+    /// JSON-RPC errors:
+    /// `{"kind":"JsonRpc","method":"sendTransaction","message":"...","code":-32000,
+    /// "reason":"NonceTooLow"}`
+    pub fn to_module_error(&self, method: &str) -> String {
+        let message = self.message();
+        let mut error = json!({
+            "kind": self.kind().to_string(),
+            "method": method,
+            "message": message,
+        });
+        if let Some(response) = self.as_error_response() {
+            error["code"] = json!(response.code);
+            if let Some(data) = &response.data {
+                error["data"] = data.clone();
+            }
+            if let Some(reason) = ethers_utils::classify_rpc_error(response.code, &response.message)
+            {
+                error["reason"] = json!(reason.to_string());
+            }
+        } else if self.kind() == ErrorKind::Reverted {
+            if let Some(data) = ethers_utils::find_revert_data(&message) {
+                error["data"] = json!(format!("0x{}", hex::encode(data)));
+            }
+        }
+        error.to_string()
+    }
+}
+
+fn provider_error_kind(error: &ProviderError) -> ErrorKind {
+    if let Some(response) = error.as_error_response() {
+        let reason = ethers_utils::classify_rpc_error(response.code, &response.message);
+        return match response.code {
+            // EIP-1193 error of requests rejected by the user
+            4001 => ErrorKind::SignerRejected,
+            _ if reason == Some(RpcErrorReason::Reverted) => ErrorKind::Reverted,
+            _ => ErrorKind::JsonRpc,
+        };
+    }
+    match error {
+        ProviderError::EnsError(_) | ProviderError::EnsNotOwned(_) => ErrorKind::NotFound,
+        ProviderError::SignerUnavailable => ErrorKind::SignerRejected,
+        _ => ErrorKind::Transport,
+    }
+}
+
+/// Runs the body of a Module method, rendering its error with `WrapperError::to_module_error`
+pub fn module_method<T>(
+    method: &str,
+    body: impl FnOnce() -> Result<T, WrapperError>,
+) -> Result<T, String> {
+    body().map_err(|e| e.to_module_error(method))
 }
 
 impl From<ethers_providers::ProviderError> for WrapperError {
@@ -25,4 +169,17 @@ impl From<ethers_utils::EncodeError> for WrapperError {
             _ => WrapperError::EncodeError(src),
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<SignerError> for WrapperError {
+    fn from(src: SignerError) -> Self {
+        WrapperError::SignerError(src)
+    }
+}
+
+/// Errors of the mapping helpers, which validate the arguments of the Module methods
+impl From<String> for WrapperError {
+    fn from(src: String) -> Self {
+        WrapperError::InvalidInput(src)
+    }
+}
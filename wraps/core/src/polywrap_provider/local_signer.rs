@@ -0,0 +1,79 @@
+use std::str::FromStr;
+
+use ethers_core::{
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address, Signature, H256,
+    },
+    utils::hash_message,
+};
+use ethers_signers::{LocalWallet, Signer as EthersSigner};
+
+use super::signer::{Signer, SignerError};
+
+/// Signer holding a secp256k1 private key, which signs inside the wrap
+/// instead of through the wallet plugin
+#[derive(Clone, Debug)]
+pub struct LocalSigner {
+    wallet: LocalWallet,
+}
+
+impl LocalSigner {
+    /// Creates a signer from a hex encoded private key, with or without `0x` prefix
+    pub fn new(private_key: &str, chain_id: u64) -> Result<Self, SignerError> {
+        let key = private_key.strip_prefix("0x").unwrap_or(private_key);
+        // the wallet error doesn't include the key, so it can be surfaced
+        let wallet = LocalWallet::from_str(key)
+            .map_err(|e| SignerError::InvalidPrivateKey(e.to_string()))?
+            .with_chain_id(chain_id);
+        Ok(Self { wallet })
+    }
+
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    /// Gets the wallet's chain id
+    pub fn chain_id(&self) -> u64 {
+        self.wallet.chain_id()
+    }
+
+    /// Signs the EIP-712 digest of a typed data payload
+    pub fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, SignerError> {
+        let encoded = payload
+            .encode_eip712()
+            .map_err(|e| SignerError::Eip712Error(e.to_string()))?;
+        self.wallet
+            .sign_hash(H256::from(encoded))
+            .map_err(|e| SignerError::WalletError(e.to_string()))
+    }
+}
+
+impl Signer for LocalSigner {
+    fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, SignerError> {
+        let hash = hash_message(message);
+        self.wallet
+            .sign_hash(hash)
+            .map_err(|e| SignerError::WalletError(e.to_string()))
+    }
+
+    fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, SignerError> {
+        // the signature makes the key's address the sender, whatever `from` is
+        if let Some(from) = tx.from().filter(|from| **from != self.address()) {
+            return Err(SignerError::InvalidSender(format!(
+                "transaction from {:#x} can't be signed with the private key of {:#x}",
+                from,
+                self.address()
+            )));
+        }
+        self.wallet
+            .sign_transaction_sync(tx)
+            .map_err(|e| SignerError::WalletError(e.to_string()))
+    }
+}
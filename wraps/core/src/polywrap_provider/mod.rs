@@ -1,3 +1,5 @@
 pub mod error;
+pub mod local_signer;
+pub mod nonce_manager;
 pub mod provider;
 pub mod signer;
\ No newline at end of file
@@ -0,0 +1,42 @@
+use ethers_core::types::{Address, BlockNumber, U256};
+use ethers_providers::ProviderError;
+
+use super::provider::{Provider, WrapProvider};
+
+/// Hands out sequential nonces to the transactions of an address, so that transactions sent
+/// back to back don't reuse a nonce before the previous ones are mined
+#[derive(Clone, Debug)]
+pub struct NonceManager {
+    address: Address,
+    next_nonce: Option<U256>,
+    /// Nonce following the highest one set explicitly by a transaction
+    min_nonce: U256,
+}
+
+impl NonceManager {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            next_nonce: None,
+            min_nonce: U256::zero(),
+        }
+    }
+
+    /// Returns the next nonce of the address. The first one is its transaction count at the
+    /// pending block, which accounts for the transactions still in the mempool
+    pub fn next(&mut self, provider: &WrapProvider) -> Result<U256, ProviderError> {
+        let nonce = match self.next_nonce {
+            Some(nonce) => nonce,
+            None => provider
+                .get_transaction_count(self.address, Some(BlockNumber::Pending.into()))?,
+        }
+        .max(self.min_nonce);
+        self.next_nonce = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Records a nonce set explicitly by a transaction, so the following nonces come after it
+    pub fn record(&mut self, nonce: U256) {
+        self.min_nonce = self.min_nonce.max(nonce + 1);
+    }
+}
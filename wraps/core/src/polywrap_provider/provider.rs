@@ -1,16 +1,24 @@
 use std::fmt::Debug;
+use std::str::FromStr;
 
+use ethers_core::abi::{decode, ParamType, Token};
 use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::transaction::eip2930::AccessListWithGasUsed;
 use ethers_core::types::{
-    Block, BlockId, BlockNumber, Bytes, FeeHistory, NameOrAddress, Transaction, TransactionReceipt,
-    TxHash, U256,
+    Address, Block, BlockId, BlockNumber, Bytes, Chain, FeeHistory, Filter, Log, NameOrAddress,
+    Selector, Transaction, TransactionReceipt, TxHash, U256, U64,
 };
 use ethers_core::utils;
-use ethers_providers::{ProviderError, RpcError};
+use ethers_providers::{ens, JsonRpcError, ProviderError, RpcError};
+use ethers_utils::{
+    BlockOverrides, CallFrame, CallTracerOptions, FeePolicy, RetryPolicy, RetryableError,
+    StateOverride,
+};
 use polywrap_wasm_rs::JSON;
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
+use crate::mapping::from_wrap_retry_policy;
 use crate::wrap::connection::Connection;
 use crate::wrap::imported::{
     ArgsRequest, ArgsWaitForTransaction, ProviderConnection, ProviderModule,
@@ -34,6 +42,13 @@ pub trait Provider {
         block_hash_or_number: T,
     ) -> Result<Option<Block<TxHash>>, ProviderError>;
 
+    fn get_block_with_txs<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<Block<Transaction>>, ProviderError>;
+
+    fn get_block_number(&self) -> Result<U64, ProviderError>;
+
     fn fee_history<T: Into<U256> + Send + Sync>(
         &self,
         block_count: T,
@@ -85,6 +100,43 @@ pub trait Provider {
         tx: &TypedTransaction,
         block: Option<BlockId>,
     ) -> Result<Bytes, ProviderError>;
+
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        state: Option<&StateOverride>,
+        block_overrides: Option<&BlockOverrides>,
+    ) -> Result<Bytes, ProviderError>;
+
+    fn trace_call(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        tracer: &CallTracerOptions,
+    ) -> Result<CallFrame, ProviderError>;
+
+    fn trace_transaction(
+        &self,
+        tx_hash: TxHash,
+        tracer: &CallTracerOptions,
+    ) -> Result<CallFrame, ProviderError>;
+
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, ProviderError>;
+
+    fn get_code(&self, at: Address, block: Option<BlockId>) -> Result<Bytes, ProviderError>;
+
+    fn create_access_list(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, ProviderError>;
+
+    fn resolve_name(&self, ens_name: &str) -> Result<Address, ProviderError>;
+
+    fn lookup_address(&self, address: Address) -> Result<String, ProviderError>;
+
+    fn resolve_transaction_to(&self, tx: &mut TypedTransaction) -> Result<(), ProviderError>;
 }
 
 #[derive(Error, Debug)]
@@ -99,23 +151,82 @@ pub enum ClientError {
     /// Serde JSON Error
     #[error("Client error: {0}")]
     Error(String),
+    /// JSON-RPC error response, parsed out of the text of the error
+    #[error("Client error: {text}")]
+    JsonRpcError { err: JsonRpcError, text: String },
+    /// Revert reported by the provider plugin without a JSON-RPC error response, given the
+    /// code 3 of geth's "execution reverted" errors
+    #[error("Client error: {text}")]
+    Reverted { err: JsonRpcError, text: String },
     /// Error in rpc request using provider
     #[error(transparent)]
     RpcClientError(ProviderError)
 
 }
 
-// @TODO(cbrzn): This need to be implemented before merging new packages structure
-// Relevant:
-// https://docs.rs/ethers/2.0.3/ethers/providers/struct.JsonRpcError.html
+impl ClientError {
+    /// Error of the provider plugin or of a node, keeping the JSON-RPC error response
+    /// embedded in its text
+    pub fn from_error_text(text: String) -> Self {
+        match ethers_utils::parse_rpc_error(&text) {
+            Some(payload) => ClientError::JsonRpcError {
+                err: JsonRpcError {
+                    code: payload.code,
+                    message: payload.message,
+                    data: payload.data,
+                },
+                text,
+            },
+            None if is_revert_text(&text) => ClientError::Reverted {
+                err: JsonRpcError {
+                    code: 3,
+                    message: "execution reverted".to_string(),
+                    data: ethers_utils::find_revert_data(&text)
+                        .map(|data| format!("0x{}", hex::encode(data)).into()),
+                },
+                text,
+            },
+            None => ClientError::Error(text),
+        }
+    }
+}
+
+// ethers.js reports reverted calls with its CALL_EXCEPTION code, and ganache with a
+// "VM Exception" message
+fn is_revert_text(text: &str) -> bool {
+    text.contains("code=CALL_EXCEPTION")
+        || text.contains("VM Exception while processing transaction: revert")
+        || text.contains("execution reverted")
+}
+
+// Error responses are found like ethers.js does:
 // https://github.com/ethers-io/ethers.js/blob/9f990c57f0486728902d4b8e049536f2bb3487ee/packages/providers/src.ts/json-rpc-provider.ts#L25-L53
 impl RpcError for ClientError {
-    fn as_error_response(&self) -> Option<&ethers_providers::JsonRpcError> {
-        todo!()
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            ClientError::JsonRpcError { err, .. } | ClientError::Reverted { err, .. } => Some(err),
+            ClientError::RpcClientError(e) => e.as_error_response(),
+            _ => None,
+        }
     }
 
     fn as_serde_error(&self) -> Option<&JSON::Error> {
-        todo!()
+        match self {
+            ClientError::SerdeJson { err, .. } => Some(err),
+            ClientError::RpcClientError(e) => e.as_serde_error(),
+            _ => None,
+        }
+    }
+}
+
+// class of transient error of a failed request, if it can be retried
+fn retryable(error: &ClientError) -> Option<RetryableError> {
+    match error {
+        ClientError::JsonRpcError { err, .. } => {
+            ethers_utils::classify_retryable(Some(err.code), &err.message)
+        }
+        ClientError::Error(text) => ethers_utils::classify_retryable(None, text),
+        _ => None,
     }
 }
 
@@ -127,9 +238,15 @@ impl From<ClientError> for ProviderError {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct WrapProvider {
     pub(super) connection: Option<ProviderConnection>,
+    /// ENS registry set in the connection, overriding the default one of the chain
+    pub(super) ens_registry: Option<String>,
+    /// Policy used to estimate EIP-1559 fees instead of the default estimator
+    pub(super) fee_policy: Option<FeePolicy>,
+    /// Policy retrying requests which failed with transient errors
+    pub(super) retry_policy: RetryPolicy,
 }
 
 impl WrapProvider {
@@ -140,21 +257,45 @@ impl WrapProvider {
         });
         Self {
             connection: iprovider_connection,
+            ens_registry: connection.as_ref().and_then(|conn| conn.ens_registry.clone()),
+            fee_policy: None,
+            retry_policy: connection
+                .as_ref()
+                .and_then(|conn| conn.retry.as_ref())
+                .map(from_wrap_retry_policy)
+                .unwrap_or_default(),
         }
     }
 
+    /// Sets the policy used to estimate the EIP-1559 fees of the transactions it fills
+    pub fn with_fee_policy(mut self, fee_policy: Option<FeePolicy>) -> Self {
+        self.fee_policy = fee_policy;
+        self
+    }
+
     pub fn request<T: Serialize + Send + Sync, R: DeserializeOwned>(
         &self,
         method: &str,
         params: T,
     ) -> Result<R, ProviderError> {
-        let params_v: serde_json::Value = JSON::to_value(&params).unwrap();
-        let res = ProviderModule::request(&ArgsRequest {
-            method: method.to_string(),
-            params: Some(params_v.into()),
-            connection: self.connection.clone(),
-        })
-        .map_err(|err| ClientError::Error(err))?;
+        let params_v: serde_json::Value = JSON::to_value(&params)?;
+        let mut attempt = 1;
+        let res = loop {
+            let res = ProviderModule::request(&ArgsRequest {
+                method: method.to_string(),
+                params: Some(params_v.clone().into()),
+                connection: self.connection.clone(),
+            })
+            .map_err(ClientError::from_error_text);
+            match res {
+                Err(error)
+                    if self.retry_policy.should_retry(method, retryable(&error), attempt) =>
+                {
+                    attempt += 1;
+                }
+                res => break res?,
+            }
+        };
         let res = JSON::from_value(res.into()).map_err(|err| ClientError::SerdeJson {
             err,
             text: "from str failed".to_string(),
@@ -176,9 +317,51 @@ impl WrapProvider {
             timeout,
             connection: self.connection.clone(),
         })
-        .map_err(|err| ClientError::Error(err))?;
+        .map_err(ClientError::from_error_text)?;
         Ok(res)
     }
+
+    /// Returns the ENS registry set in the connection or, if none is set, the registry
+    /// deployed by ENS on the connected chain
+    fn ens_registry(&self) -> Result<Address, ProviderError> {
+        if let Some(registry) = &self.ens_registry {
+            return Address::from_str(registry).map_err(|e| {
+                ProviderError::CustomError(format!("Invalid ENS registry address {registry}: {e}"))
+            });
+        }
+
+        let chain_id = self.get_chainid()?;
+        match Chain::try_from(chain_id.as_u64()) {
+            Ok(Chain::Mainnet) | Ok(Chain::Goerli) | Ok(Chain::Sepolia) => Ok(ens::ENS_ADDRESS),
+            _ => Err(ProviderError::CustomError(format!(
+                "No ENS registry known for chain {chain_id}. Set `ensRegistry` in the connection."
+            ))),
+        }
+    }
+
+    /// Calls `selector` on the resolver of `ens_name`, as set in the ENS registry
+    fn query_resolver(
+        &self,
+        param: ParamType,
+        ens_name: &str,
+        selector: Selector,
+    ) -> Result<Token, ProviderError> {
+        let registry = self.ens_registry()?;
+
+        let data = self.call(&ens::get_resolver(registry, ens_name).into(), None)?;
+        let resolver = decode(&[ParamType::Address], &data)
+            .ok()
+            .and_then(|mut tokens| tokens.pop())
+            .and_then(Token::into_address)
+            .filter(|resolver| !resolver.is_zero())
+            .ok_or_else(|| ProviderError::EnsError(ens_name.to_string()))?;
+
+        let data = self.call(&ens::resolve(resolver, selector, ens_name, None).into(), None)?;
+        decode(&[param], &data)
+            .ok()
+            .and_then(|mut tokens| tokens.pop())
+            .ok_or_else(|| ProviderError::EnsError(ens_name.to_string()))
+    }
 }
 
 impl Provider for WrapProvider {
@@ -189,11 +372,7 @@ impl Provider for WrapProvider {
         block: Option<BlockId>,
     ) -> Result<U256, ProviderError> {
         let from = match from.into() {
-            NameOrAddress::Name(ens_name) => {
-                return Err(ProviderError::EnsError(format!(
-                    "Cannot resolve ENS name {ens_name}. ENS name resolution is not supported."
-                )))
-            }
+            NameOrAddress::Name(ens_name) => self.resolve_name(&ens_name)?,
             NameOrAddress::Address(addr) => addr,
         };
 
@@ -229,6 +408,19 @@ impl Provider for WrapProvider {
         self.get_block_gen(block_hash_or_number.into(), false)
     }
 
+    /// Gets the block at `block_hash_or_number` (full transactions included)
+    fn get_block_with_txs<T: Into<BlockId> + Send + Sync>(
+        &self,
+        block_hash_or_number: T,
+    ) -> Result<Option<Block<Transaction>>, ProviderError> {
+        self.get_block_gen(block_hash_or_number.into(), true)
+    }
+
+    /// Gets the number of the latest block
+    fn get_block_number(&self) -> Result<U64, ProviderError> {
+        self.request("eth_blockNumber", ())
+    }
+
     fn fee_history<T: Into<U256> + Send + Sync>(
         &self,
         block_count: T,
@@ -274,12 +466,10 @@ impl Provider for WrapProvider {
     fn fill_gas_fees(&self, tx: &mut TypedTransaction) -> Result<(), ProviderError> {
         match tx {
             TypedTransaction::Eip2930(_) | TypedTransaction::Legacy(_) => {
-                let gas_price = if tx.gas_price().is_some() {
-                    tx.gas_price().unwrap()
-                } else {
-                    self.get_gas_price()?
-                };
-                tx.set_gas_price(gas_price);
+                if tx.gas_price().is_none() {
+                    let gas_price = self.get_gas_price()?;
+                    tx.set_gas_price(gas_price);
+                }
             }
             TypedTransaction::Eip1559(ref mut inner) => {
                 if inner.max_fee_per_gas.is_none() || inner.max_priority_fee_per_gas.is_none() {
@@ -310,17 +500,16 @@ impl Provider for WrapProvider {
             .base_fee_per_gas
             .ok_or_else(|| ProviderError::CustomError("EIP-1559 not activated".into()))?;
 
-        let fee_history = self.fee_history(
-            utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
-            BlockNumber::Latest,
-            &[utils::EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
-        )?;
-
-        // use the provided fee estimator function, or fallback to the default implementation.
-        let (max_fee_per_gas, max_priority_fee_per_gas) = if let Some(es) = estimator {
-            es(base_fee_per_gas, fee_history.reward)
-        } else {
-            utils::eip1559_default_estimator(base_fee_per_gas, fee_history.reward)
+        let (block_count, reward_percentiles) = fee_history_params(self.fee_policy.as_ref());
+        let fee_history =
+            self.fee_history(block_count, BlockNumber::Latest, &reward_percentiles)?;
+
+        // use the provided fee estimator function, then the fee policy, or fallback to the
+        // default implementation.
+        let (max_fee_per_gas, max_priority_fee_per_gas) = match (estimator, &self.fee_policy) {
+            (Some(es), _) => es(base_fee_per_gas, fee_history.reward),
+            (None, Some(policy)) => policy.estimate(base_fee_per_gas, &fee_history.reward),
+            (None, None) => utils::eip1559_default_estimator(base_fee_per_gas, fee_history.reward),
         };
 
         Ok((max_fee_per_gas, max_priority_fee_per_gas))
@@ -335,15 +524,7 @@ impl Provider for WrapProvider {
         tx: &TypedTransaction,
         block: Option<BlockId>,
     ) -> Result<U256, ProviderError> {
-        let tx = utils::serialize(tx);
-        // Some nodes (e.g. old Optimism clients) don't support a block ID being passed as a param,
-        // so refrain from defaulting to BlockNumber::Latest.
-        let params = if let Some(block_id) = block {
-            vec![tx, utils::serialize(&block_id)]
-        } else {
-            vec![tx]
-        };
-        self.request("eth_estimateGas", params)
+        self.request("eth_estimateGas", estimate_gas_params(tx, block))
     }
 
     fn fill_transaction(
@@ -352,11 +533,7 @@ impl Provider for WrapProvider {
         block: Option<BlockId>,
     ) -> Result<(), ProviderError> {
         // set the ENS name
-        if let Some(NameOrAddress::Name(ref ens_name)) = tx.to() {
-            return Err(ProviderError::EnsError(format!(
-                "Cannot resolve ENS name {ens_name}. ENS name resolution is not supported."
-            )));
-        }
+        self.resolve_transaction_to(tx)?;
 
         // fill gas price
         self.fill_gas_fees(tx)?;
@@ -384,11 +561,7 @@ impl Provider for WrapProvider {
         block: Option<BlockId>,
     ) -> Result<U256, ProviderError> {
         let from = match from.into() {
-            NameOrAddress::Name(ens_name) => {
-                return Err(ProviderError::EnsError(format!(
-                    "Cannot resolve ENS name {ens_name}. ENS name resolution is not supported."
-                )))
-            }
+            NameOrAddress::Name(ens_name) => self.resolve_name(&ens_name)?,
             NameOrAddress::Address(addr) => addr,
         };
 
@@ -419,8 +592,129 @@ impl Provider for WrapProvider {
     /// (as bytes) of executing it. This is free, since it does not change any state on the
     /// blockchain.
     fn call(&self, tx: &TypedTransaction, block: Option<BlockId>) -> Result<Bytes, ProviderError> {
+        self.call_with_overrides(tx, block, None, None)
+    }
+
+    /// Executes a call with the state of some accounts, and the block it runs in, overridden
+    fn call_with_overrides(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        state: Option<&StateOverride>,
+        block_overrides: Option<&BlockOverrides>,
+    ) -> Result<Bytes, ProviderError> {
+        let tx = utils::serialize(tx);
+        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let mut params = vec![tx, block];
+        // block overrides come after the state overrides, which are then sent even if empty
+        if state.is_some() || block_overrides.is_some() {
+            params.push(utils::serialize(&state.cloned().unwrap_or_default()));
+        }
+        if let Some(block_overrides) = block_overrides {
+            params.push(utils::serialize(block_overrides));
+        }
+        self.request("eth_call", params)
+    }
+
+    /// Creates the access list of the storage the transaction would read and write, with the gas
+    /// it would use with that list
+    fn create_access_list(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, ProviderError> {
+        let tx = utils::serialize(tx);
+        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        self.request("eth_createAccessList", [tx, block])
+    }
+
+    /// Traces the calls made by executing a transaction on top of a block, with the
+    /// `callTracer` of the debug API
+    fn trace_call(
+        &self,
+        tx: &TypedTransaction,
+        block: Option<BlockId>,
+        tracer: &CallTracerOptions,
+    ) -> Result<CallFrame, ProviderError> {
         let tx = utils::serialize(tx);
         let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
-        self.request("eth_call", [tx, block])
+        self.request("debug_traceCall", [tx, block, tracer.to_value()])
+    }
+
+    /// Traces the calls made by a mined transaction, with the `callTracer` of the debug API
+    fn trace_transaction(
+        &self,
+        tx_hash: TxHash,
+        tracer: &CallTracerOptions,
+    ) -> Result<CallFrame, ProviderError> {
+        let tx_hash = utils::serialize(&tx_hash);
+        self.request("debug_traceTransaction", [tx_hash, tracer.to_value()])
+    }
+
+    /// Returns an array (possibly empty) of logs that match the filter
+    fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>, ProviderError> {
+        self.request("eth_getLogs", [filter])
+    }
+
+    /// Returns the deployed code at a given address
+    fn get_code(&self, at: Address, block: Option<BlockId>) -> Result<Bytes, ProviderError> {
+        let at = utils::serialize(&at);
+        let block = utils::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        self.request("eth_getCode", [at, block])
+    }
+
+    /// Returns the address an ENS name resolves to
+    fn resolve_name(&self, ens_name: &str) -> Result<Address, ProviderError> {
+        self.query_resolver(ParamType::Address, ens_name, ens::ADDR_SELECTOR)?
+            .into_address()
+            .filter(|address| !address.is_zero())
+            .ok_or_else(|| ProviderError::EnsError(ens_name.to_string()))
+    }
+
+    /// Returns the primary ENS name of an address, checking that the name resolves
+    /// back to the address
+    fn lookup_address(&self, address: Address) -> Result<String, ProviderError> {
+        let ens_name = ens::reverse_address(address);
+        let domain = self
+            .query_resolver(ParamType::String, &ens_name, ens::NAME_SELECTOR)?
+            .into_string()
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| ProviderError::EnsError(ens_name.clone()))?;
+
+        if self.resolve_name(&domain)? != address {
+            return Err(ProviderError::EnsNotOwned(domain));
+        }
+        Ok(domain)
+    }
+
+    /// Replaces an ENS name set as the recipient of a transaction with its address
+    fn resolve_transaction_to(&self, tx: &mut TypedTransaction) -> Result<(), ProviderError> {
+        if let Some(NameOrAddress::Name(ens_name)) = tx.to().cloned() {
+            let address = self.resolve_name(&ens_name)?;
+            tx.set_to(address);
+        }
+        Ok(())
+    }
+}
+
+// block count and reward percentiles of the fee history used to estimate EIP-1559 fees
+fn fee_history_params(fee_policy: Option<&FeePolicy>) -> (u64, Vec<f64>) {
+    match fee_policy {
+        Some(policy) => (policy.block_count, policy.reward_percentiles.clone()),
+        None => (
+            utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            vec![utils::EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
+        ),
+    }
+}
+
+fn estimate_gas_params(tx: &TypedTransaction, block: Option<BlockId>) -> Vec<serde_json::Value> {
+    let tx = utils::serialize(tx);
+    // Some nodes (e.g. old Optimism clients) don't support a block ID being passed as a param,
+    // so refrain from defaulting to BlockNumber::Latest.
+    if let Some(block_id) = block {
+        vec![tx, utils::serialize(&block_id)]
+    } else {
+        vec![tx]
     }
 }
@@ -3,19 +3,22 @@ use std::str::FromStr;
 use ethers_core::{
     abi::Address,
     types::{
-        transaction::{eip2718::TypedTransaction, eip712::Eip712},
-        BlockId, Bytes, Signature,
+        transaction::eip2718::TypedTransaction,
+        Signature, TxHash, H256,
     },
+    utils::keccak256,
 };
-use ethers_providers::ProviderError;
+use ethers_providers::{ProviderError, RpcError};
 use ethers_signers::to_eip155_v;
 use polywrap_wasm_rs::ByteBuf;
 use thiserror::Error;
 
+use super::local_signer::LocalSigner;
+use super::provider::{Provider, WrapProvider};
 use crate::wrap::{
     connection::Connection,
     imported::{
-        ArgsRequest, ArgsSignMessage, ArgsSignTransaction, ArgsSignerAddress, ProviderConnection,
+        ArgsSignMessage, ArgsSignTransaction, ArgsSignerAddress, ProviderConnection,
         ProviderModule,
     },
 };
@@ -26,24 +29,34 @@ pub enum SignerError {
     #[error("error encoding eip712 struct: {0:?}")]
     Eip712Error(String),
     #[error("error in send transaction: {0:?}")]
-    SendError(String)
+    SendError(String),
+    #[error("invalid private key: {0}")]
+    InvalidPrivateKey(String),
+    #[error("error signing with private key: {0}")]
+    WalletError(String),
+    /// Error type from messages and transactions the wallet failed to sign
+    #[error("error signing with the wallet: {0}")]
+    SignError(String),
+    /// Error type from transactions whose sender isn't the address of the private key
+    #[error("invalid sender: {0}")]
+    InvalidSender(String),
+    /// Error type from signers whose address or chain id can't be obtained
+    #[error("signer unavailable: {0}")]
+    Unavailable(String),
 }
 
 pub trait Signer {
-    fn send(
-        &self,
-        tx: &TypedTransaction,
-        block: Option<BlockId>,
-    ) -> Result<Bytes, ProviderError>;
     fn sign_message<S: Send + Sync + AsRef<[u8]>>(
         &self,
         message: S,
     ) -> Result<Signature, SignerError>;
     fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, SignerError>;
-    fn sign_typed_data<T: Eip712 + Send + Sync>(
-        &self,
-        _payload: &T,
-    ) -> Result<Signature, SignerError>;
+}
+
+/// Signer connected to a node, which can broadcast the transactions it signs
+pub trait TransactionSender: Signer {
+    /// Signs and broadcasts a filled transaction, returning its hash
+    fn send(&self, tx: &TypedTransaction) -> Result<TxHash, ProviderError>;
 }
 
 #[derive(Clone, Debug)]
@@ -54,32 +67,48 @@ pub struct WrapSigner {
     chain_id: u64,
     /// Ethereum connection to use
     connection: Option<ProviderConnection>,
+    /// Signer used instead of the wallet plugin, when the connection has a private key
+    local: Option<LocalSigner>,
+    /// Provider used to broadcast transactions
+    provider: WrapProvider,
 }
 
 impl WrapSigner {
-    pub fn new(connection: &Option<Connection>) -> Self {
+    pub fn new(connection: &Option<Connection>) -> Result<Self, SignerError> {
         let iprovider_connection = connection.as_ref().map(|conn| ProviderConnection {
             network_name_or_chain_id: conn.network_name_or_chain_id.clone(),
             node: conn.node.clone(),
         });
-        let address = ProviderModule::signer_address(&ArgsSignerAddress {
-            connection: iprovider_connection.clone(),
-        })
-        .unwrap()
-        .unwrap();
-        let chain_id = ProviderModule::request(&ArgsRequest {
-            method: "eth_chainId".to_string(),
-            params: None,
-            connection: iprovider_connection.clone(),
-        })
-        .expect("failed to obtain signer chain id from provider plugin");
-        let chain_id_binding = chain_id.to_json();
-        let chain_id = chain_id_binding.as_str().unwrap();
-        Self {
-            address: Address::from_str(&address.as_str()).unwrap(),
-            chain_id: u64::from_str_radix(&chain_id[2..], 16).unwrap(),
+        // requested through the provider, to retry it like its other reads
+        let provider = WrapProvider::new(connection);
+        let chain_id = provider
+            .get_chainid()
+            .map_err(|e| SignerError::Unavailable(format!("failed to obtain chain id: {}", e)))?
+            .as_u64();
+
+        let local = match connection.as_ref().and_then(|conn| conn.private_key.as_ref()) {
+            Some(key) => Some(LocalSigner::new(key, chain_id)?),
+            None => None,
+        };
+        let address = match &local {
+            Some(local) => local.address(),
+            None => {
+                let address = ProviderModule::signer_address(&ArgsSignerAddress {
+                    connection: iprovider_connection.clone(),
+                })
+                .map_err(SignerError::Unavailable)?
+                .ok_or_else(|| SignerError::Unavailable("no signer address".to_string()))?;
+                Address::from_str(&address)
+                    .map_err(|e| SignerError::Unavailable(format!("invalid address: {}", e)))?
+            }
+        };
+        Ok(Self {
+            address,
+            chain_id,
             connection: iprovider_connection,
-        }
+            local,
+            provider,
+        })
     }
 
     pub(super) fn sign_rlp(&self, rlp: Vec<u8>) -> Result<Signature, String> {
@@ -87,7 +116,7 @@ impl WrapSigner {
             rlp: ByteBuf::from(rlp),
             connection: self.connection.clone(),
         })?;
-        Ok(Signature::from_str(&signature).unwrap())
+        Signature::from_str(&signature).map_err(|e| e.to_string())
     }
 
     pub(super) fn sign_bytes(&self, message: Vec<u8>) -> Result<Signature, String> {
@@ -95,7 +124,7 @@ impl WrapSigner {
             message: ByteBuf::from(message),
             connection: self.connection.clone(),
         })?;
-        Ok(Signature::from_str(&signature).unwrap())
+        Signature::from_str(&signature).map_err(|e| e.to_string())
     }
 
     pub fn address(&self) -> Address {
@@ -106,6 +135,11 @@ impl WrapSigner {
     pub fn chain_id(&self) -> u64 {
         self.chain_id
     }
+
+    /// Signer of the private key, used instead of the wallet plugin
+    pub fn local(&self) -> Option<&LocalSigner> {
+        self.local.as_ref()
+    }
 }
 
 impl Signer for WrapSigner {
@@ -113,12 +147,17 @@ impl Signer for WrapSigner {
         &self,
         message: S,
     ) -> Result<Signature, SignerError> {
+        if let Some(local) = &self.local {
+            return local.sign_message(message);
+        }
         let bytes = message.as_ref().to_vec();
-        self.sign_bytes(bytes)
-            .map_err(|e| SignerError::Eip712Error(e))
+        self.sign_bytes(bytes).map_err(SignerError::SignError)
     }
 
     fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, SignerError> {
+        if let Some(local) = &self.local {
+            return local.sign_transaction(tx);
+        }
         // rlp must have the same chain id as v in the signature
         let chain_id = tx
             .chain_id()
@@ -134,27 +173,51 @@ impl Signer for WrapSigner {
                 sig.v = to_eip155_v(sig.v as u8 - 27, chain_id);
                 Ok(sig)
             }
-            Err(e) => Err(SignerError::Eip712Error(e)),
+            Err(e) => Err(SignerError::SignError(e)),
         }
     }
+}
 
-    fn sign_typed_data<T: Eip712 + Send + Sync>(
-        &self,
-        _payload: &T,
-    ) -> Result<Signature, SignerError> {
-        panic!("{} Not implemented.", "WrapSigner.sign_typed_data");
-        // TODO: implement sign_typed_data
-        // let encoded = payload
-        //     .encode_eip712()
-        //     .map_err(|e| Self::Error::Eip712Error(e.to_string()))?;
-        // self.sign_bytes(encoded.to_vec().unwrap()).map_err(|e| SignerError::Eip712Error(e))
+impl TransactionSender for WrapSigner {
+    fn send(&self, tx: &TypedTransaction) -> Result<TxHash, ProviderError> {
+        match self.sign_transaction(tx) {
+            Ok(signature) => {
+                let raw = tx.rlp_signed(&signature);
+                let tx_hash = H256::from(keccak256(&raw));
+                match self.provider.request::<_, TxHash>("eth_sendRawTransaction", [raw]) {
+                    Ok(_) => Ok(tx_hash),
+                    // a retry of a transaction which reached the node the first time
+                    Err(e) if is_known_transaction(&e) => Ok(tx_hash),
+                    Err(e) => Err(e),
+                }
+            }
+            // signers which can't sign transactions without sending them, like the
+            // JSON-RPC signers of browser wallets, send them with eth_sendTransaction
+            Err(SignerError::SignError(e)) if self.local.is_none() && is_unsupported(&e) => {
+                self.provider.request("eth_sendTransaction", [tx])
+            }
+            Err(e) => Err(ProviderError::CustomError(e.to_string())),
+        }
     }
+}
 
-    fn send(
-        &self,
-        _tx: &TypedTransaction,
-        _block: Option<BlockId>,
-    ) -> Result<Bytes, ethers_providers::ProviderError> {
-        todo!()
+// error of a signer which can't sign transactions: the UNSUPPORTED_OPERATION error of
+// ethers.js, or the "unsupported method" codes of EIP-1193 and JSON-RPC
+fn is_unsupported(error: &str) -> bool {
+    match ethers_utils::parse_rpc_error(error) {
+        Some(error) => error.code == 4200 || error.code == -32601,
+        None => error.contains("UNSUPPORTED_OPERATION"),
+    }
+}
+
+// error of a node which already has the transaction in its pool: "already known" for geth,
+// and "known transaction: <hash>" for older nodes
+fn is_known_transaction(error: &ProviderError) -> bool {
+    match error.as_error_response() {
+        Some(response) => {
+            let message = response.message.to_lowercase();
+            message == "already known" || message.starts_with("known transaction:")
+        }
+        None => false,
     }
 }
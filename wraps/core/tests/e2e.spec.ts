@@ -22,6 +22,7 @@ import {
   setPrimitiveToStorage,
 } from "./utils/storage";
 import { ETH_ENS_IPFS_MODULE_CONSTANTS } from "polywrap";
+import { WrapError } from "@polywrap/core-js";
 
 const { hash: namehash } = require("eth-ens-namehash");
 const contracts = {
@@ -61,6 +62,9 @@ describe("Ethereum Wrapper", () => {
 
   const ethWalletPluginUri = "wrapscan.io/polywrap/ethereum-wallet@1.0";
 
+  // the wrap fails with a JSON object, found in the reason of the client's error
+  const parseError = (error?: Error) => JSON.parse((error as WrapError).reason);
+
   beforeAll(async () => {
     await initInfra();
 
@@ -91,7 +95,7 @@ describe("Ethereum Wrapper", () => {
           })
         })
       })
-      .setRedirect("wrap://wrapscan.io/polywrap/ethers-utils@1.0.1", "fs/../../utils/build")
+      .setRedirect("wrap://wrapscan.io/polywrap/ethers-utils@1.1.0", "fs/../../utils/build")
 
     clientWithCustomSigner = new PolywrapClient(config.build());
 
@@ -125,7 +129,7 @@ describe("Ethereum Wrapper", () => {
         })
       })
     })
-    .setRedirect("wrap://wrapscan.io/polywrap/ethers-utils@1.0.1", "fs/../../utils/build")
+    .setRedirect("wrap://wrapscan.io/polywrap/ethers-utils@1.1.0", "fs/../../utils/build")
 
     clientWithWeb3Provider = new PolywrapClient(configWeb3Provider.build())
 
@@ -313,6 +317,110 @@ describe("Ethereum Wrapper", () => {
       })
     });
 
+    describe("local signer", () => {
+      // second account of the test node
+      const privateKey = "0x6cbed15c793ce57650b9877cf6fa156fbef513c4e6134f022a85b1ffdd59b2a1";
+      const wallet = new Wallet(privateKey);
+      const connection = { networkNameOrChainId: "testnet", privateKey };
+
+      it("getSignerAddress", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "getSignerAddress",
+          args: { connection },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(wallet.address.toLowerCase());
+      });
+
+      it("signMessage", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "signMessage",
+          args: { message: "Hello World", connection },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(await wallet.signMessage("Hello World"));
+      });
+
+      it("signTypedData", async () => {
+        const domain = {
+          name: "Ether Mail",
+          version: "1",
+          chainId: 1,
+          verifyingContract: "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+        };
+        const types = {
+          Person: [
+            { name: "name", type: "string" },
+            { name: "wallet", type: "address" },
+          ],
+        };
+        const message = { name: "Cow", wallet: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" };
+        const payload = {
+          domain,
+          primaryType: "Person",
+          types: {
+            EIP712Domain: [
+              { name: "name", type: "string" },
+              { name: "version", type: "string" },
+              { name: "chainId", type: "uint256" },
+              { name: "verifyingContract", type: "address" },
+            ],
+            ...types,
+          },
+          message,
+        };
+
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "signTypedData",
+          args: { payload: JSON.stringify(payload), connection },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(await wallet._signTypedData(domain, types, message));
+      });
+
+      it("sendTransactionAndWait", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+          uri,
+          method: "sendTransactionAndWait",
+          args: {
+            tx: { to: signer, value: "1000" },
+            connection,
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.from).toBe(wallet.address.toLowerCase());
+        expect(response.value.status).toBe(1);
+      });
+
+      it("sendTransaction from another address", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: {
+            tx: { to: signer, from: signer, value: "1000" },
+            connection,
+          },
+        });
+        if (response.ok) throw Error("should never happen");
+        expect(response.error?.message).toContain("can't be signed with the private key");
+      });
+
+      it("invalid private key", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "signMessage",
+          args: {
+            message: "Hello World",
+            connection: { networkNameOrChainId: "testnet", privateKey: "0x1234" },
+          },
+        });
+        expect(response.ok).toBe(false);
+      });
+    });
+
     it("getSignerAddress", async () => {
       const response = await clientWithCustomSigner.invoke<string>({
         uri,
@@ -430,6 +538,142 @@ describe("Ethereum Wrapper", () => {
       expect(awaitResponse.value.confirmations).toEqual(4);
     });
 
+    describe("sendTransactions", () => {
+      it("sends transactions with sequential nonces", async () => {
+        const txs = [1, 2, 3].map((value) => ({ to: registrarAddress, value: value.toString() }));
+        const response = await clientWithCustomSigner.invoke<Schema.SendTransactionsResult>({
+          uri,
+          method: "sendTransactions",
+          args: { txs },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.hashes).toHaveLength(3);
+        expect(response.value.failedIndex).toBeFalsy();
+
+        const provider = new ethers.providers.JsonRpcProvider(ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider);
+        const nonces = await Promise.all(
+          response.value.hashes.map(async (hash) => (await provider.getTransaction(hash)).nonce)
+        );
+        expect(nonces).toStrictEqual([nonces[0], nonces[0] + 1, nonces[0] + 2]);
+      });
+
+      it("follows the nonces set by transactions", async () => {
+        const countResponse = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "getSignerTransactionCount",
+        });
+        if (!countResponse.ok) throw countResponse.error;
+        const count = Number(countResponse.value);
+
+        const txs = [
+          { to: registrarAddress, value: "1" },
+          { to: registrarAddress, value: "1", nonce: count + 1 },
+          { to: registrarAddress, value: "1" },
+        ];
+        const response = await clientWithCustomSigner.invoke<Schema.SendTransactionsResult>({
+          uri,
+          method: "sendTransactions",
+          args: { txs },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.failedIndex).toBeFalsy();
+
+        const provider = new ethers.providers.JsonRpcProvider(ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider);
+        const nonces = await Promise.all(
+          response.value.hashes.map(async (hash) => (await provider.getTransaction(hash)).nonce)
+        );
+        expect(nonces).toStrictEqual([count, count + 1, count + 2]);
+      });
+
+      it("stops at the first rejected transaction", async () => {
+        const txs = [
+          { to: registrarAddress, value: "1" },
+          { to: registrarAddress, value: "1000000000000000000000000000" },
+          { to: registrarAddress, value: "1" },
+        ];
+        const response = await clientWithCustomSigner.invoke<Schema.SendTransactionsResult>({
+          uri,
+          method: "sendTransactions",
+          args: { txs },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.hashes).toHaveLength(1);
+        expect(response.value.failedIndex).toBe(1);
+        expect(response.value.error).toBeTruthy();
+      });
+    });
+
+    describe("replacing transactions", () => {
+      const setMining = async (mining: boolean) => {
+        const response = await clientWithCustomSigner.invoke({
+          uri: ethWalletPluginUri,
+          method: "request",
+          args: { method: mining ? "miner_start" : "miner_stop" },
+        });
+        if (!response.ok) throw response.error;
+      };
+
+      const sendPendingTransaction = async (): Promise<Schema.TxResponse> => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: { tx: { to: registrarAddress, value: "1" } },
+        });
+        if (!response.ok) throw response.error;
+        return response.value;
+      };
+
+      beforeEach(async () => await setMining(false));
+      afterEach(async () => await setMining(true));
+
+      it("speedUpTransaction", async () => {
+        const original = await sendPendingTransaction();
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "speedUpTransaction",
+          args: { txHash: original.hash, feeBump: 20 },
+        });
+        if (!response.ok) throw response.error;
+        const replacement = response.value;
+        expect(replacement.hash).not.toBe(original.hash);
+        expect(replacement.nonce).toBe(original.nonce);
+        expect(replacement.to).toBe(original.to);
+        expect(replacement.value).toBe(original.value);
+        expect(BigInt(replacement.maxFeePerGas!)).toBeGreaterThanOrEqual(
+          BigInt(original.maxFeePerGas!) * BigInt(12) / BigInt(10)
+        );
+        expect(BigInt(replacement.maxPriorityFeePerGas!)).toBeGreaterThanOrEqual(
+          BigInt(original.maxPriorityFeePerGas!) * BigInt(12) / BigInt(10)
+        );
+      });
+
+      it("cancelTransaction", async () => {
+        const original = await sendPendingTransaction();
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "cancelTransaction",
+          args: { txHash: original.hash },
+        });
+        if (!response.ok) throw response.error;
+        const replacement = response.value;
+        expect(replacement.nonce).toBe(original.nonce);
+        expect(replacement.to?.toLowerCase()).toBe(signer.toLowerCase());
+        expect(replacement.value).toBe("0");
+      });
+
+      it("rejects fee bumps below 10%", async () => {
+        const original = await sendPendingTransaction();
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "speedUpTransaction",
+          args: { txHash: original.hash, feeBump: 5 },
+        });
+        expect(response.ok).toBeFalsy();
+        if (response.ok) throw Error("should never happen");
+        expect(response.error?.message).toContain("Fee bump must be at least 10%");
+      });
+    });
+
     describe("sendTransaction", () => {
       it("using custom signer", async () => {
         const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
@@ -444,6 +688,81 @@ describe("Ethereum Wrapper", () => {
         expect(response.value).toBeDefined();
         expect(response.value.hash).toBeDefined();
       })
+      it("EIP-2930 transaction with an access list", async () => {
+        const accessList = [
+          {
+            address: viewMethodsAddress,
+            storageKeys: ["0x0000000000000000000000000000000000000000000000000000000000000000"],
+          },
+        ];
+        const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+          uri,
+          method: "sendTransactionAndWait",
+          args: {
+            tx: { to: signer, value: "1", type: 1, accessList }
+          }
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.type).toBe(1);
+
+        const tx = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "getTransaction",
+          args: { hash: response.value.transactionHash },
+        });
+        if (!tx.ok) throw tx.error;
+        expect(tx.value.type).toBe(1);
+        expect(tx.value.accessList).toEqual(accessList);
+      })
+      it("gas price with an access list sends an EIP-2930 transaction", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+          uri,
+          method: "sendTransactionAndWait",
+          args: {
+            tx: { to: signer, value: "1", gasPrice: "2000000000", accessList: [] }
+          }
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.type).toBe(1);
+      })
+      it("unsupported transaction type", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: {
+            tx: { to: signer, value: "1", type: 3 }
+          }
+        });
+        expect(response.ok).toBe(false);
+      })
+      it("rejects a legacy transaction with an access list", async () => {
+        const accessList = [{ address: viewMethodsAddress, storageKeys: [] }];
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: {
+            tx: { to: signer, value: "1", type: 0, accessList }
+          }
+        });
+        expect(response.ok).toBe(false);
+        if (response.ok) throw Error("should never happen");
+        expect(response.error?.message).toContain("can't have an access list");
+      })
+      it("returns the locally computed hash", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: {
+            tx: { to: signer, value: "1" }
+          }
+        });
+        if (!response.ok) throw response.error;
+
+        const provider = new ethers.providers.JsonRpcProvider(ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider);
+        const tx = await provider.getTransaction(response.value.hash);
+        expect(tx).not.toBeNull();
+        expect(tx.hash).toBe(response.value.hash);
+      })
       it("using provider signer", async () => {
         const response = await clientWithWeb3Provider.invoke<Schema.TxReceipt>({
           uri,
@@ -470,98 +789,538 @@ describe("Ethereum Wrapper", () => {
       })
     })
 
-    it("sendTransactionAndWait", async () => {
-      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
-        uri,
-        method: "sendTransactionAndWait",
-        args: {
-          tx: { data: contracts.SimpleStorage.bytecode }
-        }
-      });
-
-      if (!response.ok) throw response.error;
-      expect(response.value).toBeDefined();
-      expect(
-        response.value.transactionHash
-      ).toBeDefined();
-    });
+    describe("node connection", () => {
+      const connection = { node: ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider };
 
-    it("estimateTransactionGas", async () => {
-      const data = contracts.SimpleStorage.bytecode;
+      it("getTransaction", async () => {
+        const sent = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+          uri,
+          method: "sendTransactionAndWait",
+          args: {
+            tx: { to: signer, value: "1" }
+          }
+        });
+        if (!sent.ok) throw sent.error;
 
-      const response = await clientWithCustomSigner.invoke<string>({
-        uri,
-        method: "estimateTransactionGas",
-        args: {
-          tx: {
-            data: data,
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "getTransaction",
+          args: {
+            hash: sent.value.transactionHash,
+            connection,
           },
-        },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.hash).toBe(sent.value.transactionHash);
+        expect(response.value.timestamp).toBeTruthy();
+        expect(Number(response.value.chainId)).toBeGreaterThan(0);
       });
 
-      if (!response.ok) throw response.error;
-      expect(response.value).toBeDefined();
-      const num = ethers.BigNumber.from(response.value);
-      expect(num.gt(0)).toBeTruthy();
+      it("estimateEip1559Fees", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.Eip1559FeesEstimate>({
+          uri,
+          method: "estimateEip1559Fees",
+          args: { connection },
+        });
+        if (!response.ok) throw response.error;
+        expect(BigInt(response.value.maxFeePerGas)).toBeGreaterThan(BigInt(0));
+      });
     });
 
-    it("deployContract", async () => {
-      const response = await clientWithCustomSigner.invoke<string>({
-        uri,
-        method: "deployContract",
-        args: {
-          abi: JSON.stringify(contracts.SimpleStorage.abi),
-          bytecode: contracts.SimpleStorage.bytecode,
-        }
+    describe("blocks", () => {
+      it("getBlockNumber", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "getBlockNumber",
+          args: {},
+        });
+        if (!response.ok) throw response.error;
+        const provider = new ethers.providers.JsonRpcProvider(ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider);
+        expect(Number(response.value)).toBe(await provider.getBlockNumber());
       });
 
-      if (!response.ok) throw response.error;
-      expect(response.value).toBeDefined();
-      expect(response.value).toContain("0x");
-    });
+      it("getBlock by tag, number and hash", async () => {
+        const latest = await clientWithCustomSigner.invoke<Schema.Block>({
+          uri,
+          method: "getBlock",
+          args: {},
+        });
+        if (!latest.ok) throw latest.error;
+        expect(latest.value.hash).toBeTruthy();
+        expect(BigInt(latest.value.gasLimit)).toBeGreaterThan(BigInt(0));
+        expect(latest.value.fullTransactions).toBeFalsy();
 
-    it("estimateContractCallGas", async () => {
-      const label = "0x" + keccak256("testwhatever2");
-      const response = await clientWithCustomSigner.invoke<string>({
-        uri,
-        method: "estimateContractCallGas",
-        args: {
-          address: registrarAddress,
-          method: "function register(bytes32 label, address owner)",
-          args: [label, signer],
-        },
+        const byNumber = await clientWithCustomSigner.invoke<Schema.Block>({
+          uri,
+          method: "getBlock",
+          args: { block: latest.value.number },
+        });
+        if (!byNumber.ok) throw byNumber.error;
+        expect(byNumber.value.hash).toBe(latest.value.hash);
+
+        const byHash = await clientWithCustomSigner.invoke<Schema.Block>({
+          uri,
+          method: "getBlock",
+          args: { block: latest.value.hash },
+        });
+        if (!byHash.ok) throw byHash.error;
+        expect(byHash.value.number).toBe(latest.value.number);
+        expect(byHash.value.parentHash).toBe(latest.value.parentHash);
       });
 
-      if (!response.ok) throw response.error;
-      expect(response.value).toBeDefined();
-      const num = ethers.BigNumber.from(response.value);
-      expect(num.gt(0)).toBeTruthy();
-    });
+      it("getBlockWithTransactions", async () => {
+        const sent = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+          uri,
+          method: "sendTransactionAndWait",
+          args: { tx: { to: registrarAddress, value: "1" } },
+        });
+        if (!sent.ok) throw sent.error;
 
-    it("callContractView", async () => {
-      const node = namehash("whatever.eth");
-      const response = await clientWithCustomSigner.invoke<string>({
-        uri,
-        method: "callContractView",
-        args: {
-          address: ensAddress,
-          method: "function resolver(bytes32 node) external view returns (address)",
-          args: [node]
-        }
+        const response = await clientWithCustomSigner.invoke<Schema.Block>({
+          uri,
+          method: "getBlockWithTransactions",
+          args: { block: sent.value.blockNumber },
+        });
+        if (!response.ok) throw response.error;
+        const block = response.value;
+        expect(block.transactions).toContain(sent.value.transactionHash);
+        const tx = block.fullTransactions!.find((tx) => tx.hash === sent.value.transactionHash);
+        expect(tx?.value).toBe("1");
+        expect(tx?.blockHash).toBe(block.hash);
+
+        const provider = new ethers.providers.JsonRpcProvider(ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider);
+        const onChain = await provider.getTransaction(sent.value.transactionHash);
+        expect(BigInt(tx!.r!)).toBe(BigInt(onChain.r!));
+        expect(BigInt(tx!.s!)).toBe(BigInt(onChain.s!));
       });
 
-      if (!response.ok) throw response.error;
-      expect(response.value).toBeDefined();
-      expect(response.value).toBe("0x0000000000000000000000000000000000000000");
+      it("getBlock with an invalid block", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.Block>({
+          uri,
+          method: "getBlock",
+          args: { block: "newest" },
+        });
+        expect(response.ok).toBeFalsy();
+      });
     });
 
-    it("callContractStatic (no error)", async () => {
-      const label = "0x" + keccak256("testwhatever");
-      const response = await clientWithCustomSigner.invoke<Schema.StaticTxResult>({
-        uri,
-        method: "callContractStatic",
-        args: {
-          address: registrarAddress,
+    describe("errors", () => {
+      it("invalid input", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "getBalance",
+          args: { address: "0xinvalid" },
+        });
+        if (response.ok) throw Error("should never happen");
+        const error = parseError(response.error);
+        expect(error.kind).toBe("InvalidInput");
+        expect(error.method).toBe("getBalance");
+        expect(error.message).toContain("Invalid address");
+      });
+
+      it("transaction not found", async () => {
+        const hash = "0x" + "ab".repeat(32);
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "getTransaction",
+          args: { hash },
+        });
+        if (response.ok) throw Error("should never happen");
+        const error = parseError(response.error);
+        expect(error.kind).toBe("NotFound");
+        expect(error.message).toContain(hash);
+      });
+
+      it("block not found", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.Block>({
+          uri,
+          method: "getBlock",
+          args: { block: "1000000000" },
+        });
+        if (response.ok) throw Error("should never happen");
+        expect(parseError(response.error).kind).toBe("NotFound");
+      });
+
+      it("invalid transaction hash", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "cancelTransaction",
+          args: { txHash: "0x1234" },
+        });
+        if (response.ok) throw Error("should never happen");
+        expect(parseError(response.error)).toMatchObject({
+          kind: "InvalidInput",
+          method: "cancelTransaction",
+        });
+      });
+
+      it("JSON-RPC error with code and reason", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: { tx: { to: signer, value: "1" + "0".repeat(30), gasLimit: "21000" } },
+        });
+        if (response.ok) throw Error("should never happen");
+        const error = parseError(response.error);
+        expect(error.kind).toBe("JsonRpc");
+        expect(typeof error.code).toBe("number");
+        expect(error.reason).toBe("InsufficientFunds");
+      });
+    });
+
+    describe("retries", () => {
+      const connection: Schema.Connection = {
+        networkNameOrChainId: "testnet",
+        retry: { maxAttempts: 3, retryOn: ["RATE_LIMITED", "TIMEOUT"] },
+      };
+
+      // client whose node is rate limited on the first `failures` requests of `eth_getBalance`
+      const rateLimitedClient = (failures: number) => {
+        const node = new ethers.providers.JsonRpcProvider(ETH_ENS_IPFS_MODULE_CONSTANTS.ethereumProvider);
+        const calls = { getBalance: 0 };
+        const provider = {
+          request: async ({ method, params }: { method: string; params?: unknown[] }) => {
+            if (method === "eth_getBalance" && calls.getBalance++ < failures) {
+              throw { code: 429, message: "Too Many Requests" };
+            }
+            return node.send(method, params ?? []);
+          },
+        };
+        const config = new PolywrapClientConfigBuilder()
+          .addDefaults()
+          .setPackages({
+            // @ts-ignore
+            [ethWalletPluginUri]: ethereumWalletPlugin({
+              connections: new Connections({
+                networks: { testnet: new Connection({ provider }) },
+                defaultNetwork: "testnet",
+              })
+            })
+          })
+          .setRedirect("wrap://wrapscan.io/polywrap/ethers-utils@1.1.0", "fs/../../utils/build");
+        return { client: new PolywrapClient(config.build()), calls };
+      };
+
+      it("retries reads failing with a transient error", async () => {
+        const { client, calls } = rateLimitedClient(2);
+        const response = await client.invoke<string>({
+          uri,
+          method: "getBalance",
+          args: { address: signer, connection },
+        });
+        if (!response.ok) throw response.error;
+        expect(BigInt(response.value)).toBeGreaterThan(BigInt(0));
+        expect(calls.getBalance).toBe(3);
+      });
+
+      it("fails after the last attempt", async () => {
+        const { client, calls } = rateLimitedClient(3);
+        const response = await client.invoke<string>({
+          uri,
+          method: "getBalance",
+          args: { address: signer, connection },
+        });
+        if (response.ok) throw Error("should never happen");
+        expect(parseError(response.error).message).toContain("Too Many Requests");
+        expect(calls.getBalance).toBe(3);
+      });
+
+      it("doesn't retry without a retry policy", async () => {
+        const { client, calls } = rateLimitedClient(1);
+        const response = await client.invoke<string>({
+          uri,
+          method: "getBalance",
+          args: { address: signer },
+        });
+        expect(response.ok).toBeFalsy();
+        expect(calls.getBalance).toBe(1);
+      });
+
+      it("errors which aren't transient aren't retried", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.TxResponse>({
+          uri,
+          method: "sendTransaction",
+          args: {
+            tx: { to: signer, value: "1" + "0".repeat(30), gasLimit: "21000" },
+            connection,
+          },
+        });
+        if (response.ok) throw Error("should never happen");
+        expect(parseError(response.error).reason).toBe("InsufficientFunds");
+      });
+    });
+
+    describe("fee history", () => {
+      it("getFeeHistory", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.FeeHistory>({
+          uri,
+          method: "getFeeHistory",
+          args: { blockCount: 2, rewardPercentiles: ["25", "75"] },
+        });
+        if (!response.ok) throw response.error;
+        const history = response.value;
+        expect(history.baseFeePerGas.length).toBe(history.gasUsedRatio.length + 1);
+        expect(history.gasUsedRatio.length).toBeLessThanOrEqual(2);
+        history.reward.forEach((rewards) => expect(rewards.length).toBe(2));
+      });
+
+      it("getGasMarketSummary", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.GasMarketSummary>({
+          uri,
+          method: "getGasMarketSummary",
+          args: {},
+        });
+        if (!response.ok) throw response.error;
+        const summary = response.value;
+        expect(BigInt(summary.nextBaseFeePerGas)).toBeGreaterThan(BigInt(0));
+        expect(summary.tips.map((tip) => tip.percentile)).toStrictEqual(["10", "50", "90"]);
+      });
+    });
+
+    describe("fee policies", () => {
+      it("estimateEip1559Fees with speed presets", async () => {
+        const estimate = async (speed: string) => {
+          const response = await clientWithCustomSigner.invoke<Schema.Eip1559FeesEstimate>({
+            uri,
+            method: "estimateEip1559Fees",
+            args: { feePolicy: { speed } },
+          });
+          if (!response.ok) throw response.error;
+          return BigInt(response.value.maxFeePerGas);
+        };
+        const slow = await estimate("SLOW");
+        const fast = await estimate("FAST");
+        expect(slow).toBeGreaterThan(BigInt(0));
+        expect(fast).toBeGreaterThanOrEqual(slow);
+      });
+
+      it("estimateEip1559Fees with priority fee caps", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.Eip1559FeesEstimate>({
+          uri,
+          method: "estimateEip1559Fees",
+          args: {
+            feePolicy: {
+              blockCount: 4,
+              rewardPercentiles: ["25", "75"],
+              baseFeeMultiplier: "1.5",
+              minPriorityFee: "1000",
+              maxPriorityFee: "1000",
+            },
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.maxPriorityFeePerGas).toBe("1000");
+      });
+
+      it("estimateEip1559Fees with an invalid fee policy", async () => {
+        const estimate = async (feePolicy: Schema.FeePolicy) =>
+          clientWithCustomSigner.invoke<Schema.Eip1559FeesEstimate>({
+            uri,
+            method: "estimateEip1559Fees",
+            args: { feePolicy },
+          });
+        const unordered = await estimate({ rewardPercentiles: ["75", "25"] });
+        if (unordered.ok) throw Error("should never happen");
+        expect(unordered.error?.message).toContain("increasing order");
+
+        const multiplier = await estimate({ baseFeeMultiplier: "0" });
+        if (multiplier.ok) throw Error("should never happen");
+        expect(multiplier.error?.message).toContain("base fee multiplier");
+      });
+
+      it("callContractMethodAndWait with a fee policy", async () => {
+        const label = "0x" + keccak256("testfeepolicy");
+        const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+          uri,
+          method: "callContractMethodAndWait",
+          args: {
+            address: registrarAddress,
+            method: "function register(bytes32 label, address owner)",
+            args: [label, signer],
+            options: { feePolicy: { speed: "FAST", maxPriorityFee: "2000000000" } },
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.status).toBe(1);
+      });
+    });
+
+    it("sendTransactionAndWait", async () => {
+      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "sendTransactionAndWait",
+        args: {
+          tx: { data: contracts.SimpleStorage.bytecode }
+        }
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value).toBeDefined();
+      expect(
+        response.value.transactionHash
+      ).toBeDefined();
+    });
+
+    it("estimateTransactionGas", async () => {
+      const data = contracts.SimpleStorage.bytecode;
+
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "estimateTransactionGas",
+        args: {
+          tx: {
+            data: data,
+          },
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value).toBeDefined();
+      const num = ethers.BigNumber.from(response.value);
+      expect(num.gt(0)).toBeTruthy();
+    });
+
+    it("deployContract", async () => {
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "deployContract",
+        args: {
+          abi: JSON.stringify(contracts.SimpleStorage.abi),
+          bytecode: contracts.SimpleStorage.bytecode,
+        }
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value).toBeDefined();
+      expect(response.value).toContain("0x");
+    });
+
+    it("estimateContractCallGas", async () => {
+      const label = "0x" + keccak256("testwhatever2");
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "estimateContractCallGas",
+        args: {
+          address: registrarAddress,
+          method: "function register(bytes32 label, address owner)",
+          args: [label, signer],
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value).toBeDefined();
+      const num = ethers.BigNumber.from(response.value);
+      expect(num.gt(0)).toBeTruthy();
+    });
+
+    it("callContractView", async () => {
+      const node = namehash("whatever.eth");
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "callContractView",
+        args: {
+          address: ensAddress,
+          method: "function resolver(bytes32 node) external view returns (address)",
+          args: [node]
+        }
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value).toBeDefined();
+      expect(response.value).toBe("0x0000000000000000000000000000000000000000");
+    });
+
+    describe("call overrides", () => {
+      // runtime code returning the 32 bytes word 42
+      const code = "0x602a60005260206000f3";
+      const address = "0x1000000000000000000000000000000000000001";
+
+      it("callContractView with overridden code", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "callContractView",
+          args: {
+            address,
+            method: "function get() view returns (uint256)",
+            stateOverrides: [{ address, code }],
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe("42");
+      });
+
+      it("callContractStatic with overridden code and balance", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.StaticTxResult>({
+          uri,
+          method: "callContractStatic",
+          args: {
+            address,
+            method: "function get() returns (uint256)",
+            stateOverrides: [{ address, code }, { address: signer, balance: "1000000000000000000000000" }],
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.error).toBeFalsy();
+        expect(response.value.result).toBe("42");
+      });
+
+      it("callContractStatic which reverts without the overridden storage", async () => {
+        // runtime code returning 42 if its storage slot 0 is set, and reverting otherwise
+        const runtime = "60005415601157602a60005260206000f35b600080fd";
+        const deployed = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "deployContract",
+          args: { abi: "[]", bytecode: "0x6016600c60003960166000f3" + runtime },
+        });
+        if (!deployed.ok) throw deployed.error;
+        const callStatic = (stateOverrides?: Schema.AccountOverride[]) =>
+          clientWithCustomSigner.invoke<Schema.StaticTxResult>({
+            uri,
+            method: "callContractStatic",
+            args: {
+              address: deployed.value,
+              method: "function get() returns (uint256)",
+              stateOverrides,
+            },
+          });
+
+        const reverted = await callStatic();
+        if (!reverted.ok) throw reverted.error;
+        expect(reverted.value.error).toBeTruthy();
+
+        const slot = "0x" + "0".repeat(64);
+        const value = "0x" + "0".repeat(63) + "1";
+        const response = await callStatic([
+          { address: deployed.value, stateDiff: [{ slot, value }] },
+        ]);
+        if (!response.ok) throw response.error;
+        expect(response.value.error).toBeFalsy();
+        expect(response.value.result).toBe("42");
+      });
+
+      it("rejects overriding both state and stateDiff", async () => {
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "callContractView",
+          args: {
+            address,
+            method: "function get() view returns (uint256)",
+            stateOverrides: [{ address, state: [], stateDiff: [] }],
+          },
+        });
+        expect(response.ok).toBeFalsy();
+        if (response.ok) throw Error("should never happen");
+        expect(response.error?.message).toContain("can't set both state and stateDiff");
+      });
+    });
+
+    it("callContractStatic (no error)", async () => {
+      const label = "0x" + keccak256("testwhatever");
+      const response = await clientWithCustomSigner.invoke<Schema.StaticTxResult>({
+        uri,
+        method: "callContractStatic",
+        args: {
+          address: registrarAddress,
           method: "function register(bytes32 label, address owner)",
           args: [label, signer],
         },
@@ -613,6 +1372,74 @@ describe("Ethereum Wrapper", () => {
       expect(response.value?.result).toContain("out of gas");
     });
 
+    describe("callContractStatic (decoded revert)", () => {
+      // contract whose every call reverts with `selector` followed by a single 32 bytes word
+      const deployReverter = async (selector: string, word: number): Promise<string> => {
+        const runtime =
+          "63" + selector.replace("0x", "") + "60e01b600052" +
+          "60" + word.toString(16).padStart(2, "0") + "600452" +
+          "60246000fd";
+        const bytecode = "0x6015600c60003960156000f3" + runtime;
+        const response = await clientWithCustomSigner.invoke<string>({
+          uri,
+          method: "deployContract",
+          args: { abi: "[]", bytecode },
+        });
+        if (!response.ok) throw response.error;
+        return response.value;
+      };
+
+      it("panic", async () => {
+        const address = await deployReverter("0x4e487b71", 0x11);
+        const response = await clientWithCustomSigner.invoke<Schema.StaticTxResult>({
+          uri,
+          method: "callContractStatic",
+          args: {
+            address,
+            method: "function increment()",
+          },
+        });
+
+        if (!response.ok) throw response.error;
+        expect(response.value.error).toBeTruthy();
+        const callError = response.value.callError as Schema.CallError;
+        expect(callError.kind).toBe("Panic");
+        expect(callError.name).toBe("Panic");
+        expect(callError.message).toBe("arithmetic overflow or underflow (panic code 0x11)");
+        expect(callError.args?.[0].value).toBe("17");
+        // the result keeps the error message of the provider
+        expect(response.value.result).toContain("revert");
+      });
+
+      it("custom error", async () => {
+        const errorAbi = {
+          inputs: [{ name: "code", type: "uint256" }],
+          name: "Unauthorized",
+          type: "error",
+        };
+        const selector = ethers.utils.id("Unauthorized(uint256)").slice(0, 10);
+        const address = await deployReverter(selector, 42);
+        const response = await clientWithCustomSigner.invoke<Schema.StaticTxResult>({
+          uri,
+          method: "callContractStatic",
+          args: {
+            address,
+            method: "function withdraw()",
+            abi: JSON.stringify([errorAbi]),
+          },
+        });
+
+        if (!response.ok) throw response.error;
+        expect(response.value.error).toBeTruthy();
+        const callError = response.value.callError as Schema.CallError;
+        expect(callError.kind).toBe("CustomError");
+        expect(callError.name).toBe("Unauthorized");
+        expect(callError.args?.[0].name).toBe("code");
+        expect(callError.args?.[0].value).toBe("42");
+        expect(callError.data?.startsWith(selector)).toBeTruthy();
+      });
+    });
+
     it("callContractMethod", async () => {
       const label = "0x" + keccak256("testwhatever");
       const response = await clientWithCustomSigner.invoke({
@@ -653,6 +1480,68 @@ describe("Ethereum Wrapper", () => {
       if (!response.ok) throw response.error;
       expect(response.value).toBeDefined();
     });
+
+    it("callContractMethodAndWait with an access list", async () => {
+      const label = "0x" + keccak256("testaccesslist");
+      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "callContractMethodAndWait",
+        args: {
+          address: registrarAddress,
+          method: "function register(bytes32 label, address owner)",
+          args: [label, signer],
+          options: {
+            gasPrice: "4000000000",
+            gasLimit: "200000",
+            accessList: [{ address: registrarAddress, storageKeys: [] }],
+          },
+        }
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value.type).toBe(1);
+    });
+  });
+
+  describe("access lists", () => {
+    let storageAddress: string;
+
+    beforeAll(async () => {
+      storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+    });
+
+    it("createAccessList", async () => {
+      const iface = new ethers.utils.Interface(contracts.SimpleStorage.abi);
+      const response = await clientWithCustomSigner.invoke<Schema.AccessItem[]>({
+        uri,
+        method: "createAccessList",
+        args: {
+          tx: { to: storageAddress, data: iface.encodeFunctionData("set", [42]) },
+        },
+      });
+      if (!response.ok) throw response.error;
+      const item = response.value.find((item) => item.address === storageAddress);
+      expect(item).toBeDefined();
+      expect(item?.storageKeys.length).toBeGreaterThan(0);
+    });
+
+    it("callContractMethodAndWait with autoAccessList", async () => {
+      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "callContractMethodAndWait",
+        args: {
+          address: storageAddress,
+          method: "function set(uint256 x)",
+          args: ["43"],
+          options: { autoAccessList: true },
+        },
+      });
+      if (!response.ok) throw response.error;
+      expect(response.value.status).toBe(1);
+    });
   });
 
   describe("callContractView with complex ABI", () => {
@@ -907,6 +1796,308 @@ describe("Ethereum Wrapper", () => {
     });
   });
 
+  describe("ENS", () => {
+    const name = "ethers-ens.eth";
+    let connection: Schema.Connection;
+
+    const callAndWait = async (address: string, method: string, args: string[]) => {
+      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "callContractMethodAndWait",
+        args: { address, method, args },
+      });
+      if (!response.ok) throw response.error;
+    };
+
+    beforeAll(async () => {
+      const { resolverAddress, reverseAddress } = ETH_ENS_IPFS_MODULE_CONSTANTS.ensAddresses;
+      connection = { networkNameOrChainId: "testnet", ensRegistry: ensAddress };
+
+      await callAndWait(
+        registrarAddress,
+        "function register(bytes32 label, address owner)",
+        ["0x" + keccak256("ethers-ens"), signer]
+      );
+      await callAndWait(
+        ensAddress,
+        "function setResolver(bytes32 node, address resolver)",
+        [namehash(name), resolverAddress]
+      );
+      await callAndWait(
+        resolverAddress,
+        "function setAddr(bytes32 node, address addr)",
+        [namehash(name), signer]
+      );
+      await callAndWait(reverseAddress, "function setName(string name)", [name]);
+    });
+
+    it("resolveName", async () => {
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "resolveName",
+        args: { name, connection },
+      });
+      if (!response.ok) throw response.error;
+      expect(response.value).toBe(signer.toLowerCase());
+    });
+
+    it("resolveName (unknown name)", async () => {
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "resolveName",
+        args: { name: "unknown-ethers-ens.eth", connection },
+      });
+      expect(response.ok).toBe(false);
+    });
+
+    it("resolveName (no registry on chain)", async () => {
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "resolveName",
+        args: { name },
+      });
+      if (response.ok) throw Error("should never happen");
+      expect(response.error?.message).toContain("ensRegistry");
+    });
+
+    it("lookupAddress", async () => {
+      const response = await clientWithCustomSigner.invoke<string | null>({
+        uri,
+        method: "lookupAddress",
+        args: { address: signer, connection },
+      });
+      if (!response.ok) throw response.error;
+      expect(response.value).toBe(name);
+    });
+
+    it("lookupAddress (no primary name)", async () => {
+      const response = await clientWithCustomSigner.invoke<string | null>({
+        uri,
+        method: "lookupAddress",
+        args: { address: viewMethodsAddress, connection },
+      });
+      if (!response.ok) throw response.error;
+      expect(response.value).toBeNull();
+    });
+
+    it("getBalance (ENS name)", async () => {
+      const byName = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "getBalance",
+        args: { address: name, connection },
+      });
+      if (!byName.ok) throw byName.error;
+
+      const byAddress = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "getBalance",
+        args: { address: signer, connection },
+      });
+      if (!byAddress.ok) throw byAddress.error;
+      expect(byName.value).toBe(byAddress.value);
+    });
+
+    it("sendTransaction (ENS name recipient)", async () => {
+      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "sendTransactionAndWait",
+        args: {
+          tx: { to: name, value: "1000" },
+          connection,
+        },
+      });
+      if (!response.ok) throw response.error;
+      expect(response.value.to).toBe(signer.toLowerCase());
+    });
+  });
+
+  describe("getLogs", () => {
+    const dataSetTopic = ethers.utils.id("DataSet(address,uint256)");
+
+    it("getLogs (address and topic)", async () => {
+      const storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+      await setPrimitiveToStorage(
+        contracts.SimpleStorage.abi,
+        storageAddress,
+        "100"
+      );
+
+      const response = await clientWithCustomSigner.invoke<Schema.Log[]>({
+        uri,
+        method: "getLogs",
+        args: {
+          filter: {
+            address: [storageAddress],
+            fromBlock: "0",
+            topics: [[dataSetTopic]],
+          },
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value.length).toEqual(1);
+      expect(response.value[0].address).toEqual(storageAddress);
+      expect(response.value[0].topics[0]).toEqual(dataSetTopic);
+      expect(ethers.BigNumber.from(response.value[0].data).toNumber()).toEqual(100);
+    });
+
+    it("getLogs (topic wildcard and OR-set)", async () => {
+      const storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+      await setPrimitiveToStorage(
+        contracts.SimpleStorage.abi,
+        storageAddress,
+        "100"
+      );
+
+      const hashSetTopic = ethers.utils.id("HashSet(address,string)");
+      const response = await clientWithCustomSigner.invoke<Schema.Log[]>({
+        uri,
+        method: "getLogs",
+        args: {
+          filter: {
+            address: [storageAddress],
+            fromBlock: "0",
+            topics: [[hashSetTopic, dataSetTopic], null],
+          },
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value.length).toEqual(1);
+      expect(response.value[0].topics[0]).toEqual(dataSetTopic);
+    });
+
+    it("getLogs (block tags)", async () => {
+      const storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+      await setPrimitiveToStorage(
+        contracts.SimpleStorage.abi,
+        storageAddress,
+        "100"
+      );
+
+      const response = await clientWithCustomSigner.invoke<Schema.Log[]>({
+        uri,
+        method: "getLogs",
+        args: {
+          filter: {
+            address: [storageAddress],
+            fromBlock: "earliest",
+            toBlock: "latest",
+            topics: [[dataSetTopic]],
+          },
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value.length).toEqual(1);
+      expect(response.value[0].blockHash).toBeDefined();
+    });
+  });
+
+  describe("decodeLogs", () => {
+    it("callContractMethodAndWait with ABI", async () => {
+      const storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+
+      const response = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "callContractMethodAndWait",
+        args: {
+          address: storageAddress,
+          method: "function set(uint256 x)",
+          args: ["100"],
+          abi: JSON.stringify(contracts.SimpleStorage.abi),
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      const decodedLogs = response.value.decodedLogs as Schema.DecodedLog[];
+      expect(decodedLogs.length).toEqual(1);
+      expect(decodedLogs[0].name).toEqual("DataSet");
+      expect(decodedLogs[0].signature).toEqual("DataSet(address,uint256)");
+      expect(decodedLogs[0].args[0].name).toEqual("from");
+      expect(decodedLogs[0].args[0].value).toEqual(signer.toLowerCase());
+      expect(decodedLogs[0].args[1].name).toEqual("data");
+      expect(decodedLogs[0].args[1].type).toEqual("uint256");
+      expect(decodedLogs[0].args[1].value).toEqual("100");
+    });
+
+    it("decodeLogs", async () => {
+      const storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+
+      const receipt = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "callContractMethodAndWait",
+        args: {
+          address: storageAddress,
+          method: "function setHash(string x)",
+          args: ["QmHash"],
+        },
+      });
+      if (!receipt.ok) throw receipt.error;
+      expect(receipt.value.decodedLogs).toBeFalsy();
+
+      const response = await clientWithCustomSigner.invoke<Schema.DecodedLog[]>({
+        uri,
+        method: "decodeLogs",
+        args: {
+          abi: JSON.stringify(contracts.SimpleStorage.abi),
+          logs: receipt.value.logs,
+        },
+      });
+
+      if (!response.ok) throw response.error;
+      expect(response.value.length).toEqual(1);
+      expect(response.value[0].name).toEqual("HashSet");
+      expect(response.value[0].args[1].name).toEqual("ipfsHash");
+      expect(response.value[0].args[1].value).toEqual("QmHash");
+    });
+
+    it("decodeLogs with data not matching the ABI", async () => {
+      const storageAddress = (await deployStorage(
+        contracts.SimpleStorage.abi,
+        contracts.SimpleStorage.bytecode
+      )).toLowerCase();
+
+      const receipt = await clientWithCustomSigner.invoke<Schema.TxReceipt>({
+        uri,
+        method: "callContractMethodAndWait",
+        args: {
+          address: storageAddress,
+          method: "function set(uint256 x)",
+          args: ["100"],
+        },
+      });
+      if (!receipt.ok) throw receipt.error;
+
+      const response = await clientWithCustomSigner.invoke<Schema.DecodedLog[]>({
+        uri,
+        method: "decodeLogs",
+        args: {
+          abi: JSON.stringify(contracts.SimpleStorage.abi),
+          logs: receipt.value.logs.map((log) => ({ ...log, data: "0x" })),
+        },
+      });
+      expect(response.ok).toBeFalsy();
+      if (response.ok) return;
+      expect(parseError(response.error).kind).toBe("Abi");
+    });
+  });
+
   describe("ViewMethods", () => {
 
     const testViewMethod = async (
@@ -1002,5 +2193,125 @@ describe("Ethereum Wrapper", () => {
     it("ViewMethods - getMultiMixed", async () => {
       await testViewMethod("getMultiMixed", `string,${getStructType},uint8[6]`, `["${getStringResult}",${getStructResult},${getArray1DResult}]`);
     });
+
+    describe("callContractViews", () => {
+      const call = (methodName: string, returnType: string, allowFailure?: boolean) => ({
+        address: viewMethodsAddress,
+        method: `function ${methodName}() public view returns (${returnType})`,
+        allowFailure,
+      });
+
+      it("should return the results of all calls in order", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.ContractCallResult[]>({
+          uri,
+          method: "callContractViews",
+          args: {
+            calls: [call("getBool", "bool"), call("getUint256", "uint256"), call("getArray1D", "uint8[6]")],
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value.map((r) => r.success)).toEqual([true, true, true]);
+        expect(response.value.map((r) => r.result)).toEqual(["true", getUint256Result, getArray1DResult]);
+      });
+
+      it("should return the error of calls allowed to fail", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.ContractCallResult[]>({
+          uri,
+          method: "callContractViews",
+          args: {
+            calls: [call("getBool", "bool"), call("notAMethod", "bool", true)],
+          },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value[0]).toEqual({ success: true, result: "true", error: null });
+        expect(response.value[1].success).toBe(false);
+        expect(response.value[1].error).toBeTruthy();
+      });
+
+      it("should fail if a call not allowed to fail reverts", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.ContractCallResult[]>({
+          uri,
+          method: "callContractViews",
+          args: {
+            calls: [call("getBool", "bool"), call("notAMethod", "bool")],
+          },
+        });
+        expect(response.ok).toBe(false);
+      });
+
+      it("should return no results for no calls", async () => {
+        const response = await clientWithCustomSigner.invoke<Schema.ContractCallResult[]>({
+          uri,
+          method: "callContractViews",
+          args: { calls: [] },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toEqual([]);
+      });
+    });
+
+    const testNamedViewMethod = async (methodName: string) => {
+      const abiMethod = contracts.ViewMethods.abi.find(
+        (item: { name?: string }) => item.name === methodName
+      );
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "callContractView",
+        args: {
+          address: viewMethodsAddress,
+          method: JSON.stringify(abiMethod),
+          namedOutput: true,
+        },
+      });
+      if (!response.ok) throw response.error;
+      return JSON.parse(response.value);
+    }
+
+    const getStructNamedResult = {
+      foo: getStringResult,
+      bar: getUint256Result,
+      baz: "1",
+    };
+
+    it("ViewMethods - getStruct (named output)", async () => {
+      const result = await testNamedViewMethod("getStruct");
+      expect(result).toStrictEqual(getStructNamedResult);
+    });
+
+    it("ViewMethods - getMultiNamed (named output)", async () => {
+      const result = await testNamedViewMethod("getMultiNamed");
+      expect(result).toStrictEqual({
+        obj: getStructNamedResult,
+        array1d: ["1", "2", "3", "6", "5", "4"],
+        str: getStringResult,
+      });
+    });
+
+    it("ViewMethods - getMultiUnamed (named output)", async () => {
+      const result = await testNamedViewMethod("getMultiUnamed");
+      expect(result).toStrictEqual({
+        "0": ["1", "2", "3", "6", "5", "4"],
+        "1": getStringResult,
+        "2": getStructNamedResult,
+      });
+    });
+
+    it("ViewMethods - getMultiNamed (named output - string ABI)", async () => {
+      const response = await clientWithCustomSigner.invoke<string>({
+        uri,
+        method: "callContractView",
+        args: {
+          address: viewMethodsAddress,
+          method: `function getMultiNamed() public view returns (${getStructType} obj, uint8[6] array1d, string str)`,
+          namedOutput: true,
+        },
+      });
+      if (!response.ok) throw response.error;
+      expect(JSON.parse(response.value)).toStrictEqual({
+        obj: [getStringResult, getUint256Result, "1"],
+        array1d: ["1", "2", "3", "6", "5", "4"],
+        str: getStringResult,
+      });
+    });
   });
 });
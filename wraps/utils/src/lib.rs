@@ -1,8 +1,15 @@
 use ethers_core::abi::{encode_packed, Function, Token};
-use ethers_core::types::{Address, Bytes};
+use ethers_core::types::{Address, Bytes, H256};
 use ethers_core::utils::{get_create2_address, keccak256 as keccak256_ethers};
 use ethers_utils::{
-    encode_function as utils_encode_function, encode_params as utils_encode_params,
+    decode_function as utils_decode_function,
+    decode_function_result as utils_decode_function_result, decode_params as utils_decode_params,
+    encode_event_topics as utils_encode_event_topics, encode_function as utils_encode_function,
+    encode_params as utils_encode_params, format_tokens, format_tokens_array,
+    hash_typed_data as utils_hash_typed_data, parse_signature, parse_typed_data,
+    recover_address as utils_recover_address,
+    recover_transaction_signer as utils_recover_transaction_signer,
+    verify_message as utils_verify_message, verify_typed_data as utils_verify_typed_data,
     solidity_pack as utils_solidity_pack, to_eth as utils_to_eth, to_wei as utils_to_wei,
 };
 use polywrap_wasm_rs::BigInt;
@@ -14,7 +21,7 @@ use wrap::*;
 
 impl ModuleTrait for Module {
     fn keccak256(args: wrap::ArgsKeccak256) -> Result<String, String> {
-        let decoded = Bytes::from_str(&args.value).unwrap();
+        let decoded = Bytes::from_str(&args.value).map_err(|e| format!("Invalid value: {}", e))?;
         let hash = keccak256_ethers(decoded);
         Ok(format!("{}", Bytes::from(hash)).to_string())
     }
@@ -22,16 +29,62 @@ impl ModuleTrait for Module {
     fn keccak256_bytes_encode_packed(
         args: wrap::ArgsKeccak256BytesEncodePacked,
     ) -> Result<String, String> {
-        let bytes = Bytes::from_str(&args.value).unwrap();
+        let bytes = Bytes::from_str(&args.value).map_err(|e| format!("Invalid value: {}", e))?;
         let bytes = Token::Bytes(bytes.to_vec());
-        let encoded = keccak256_ethers(encode_packed(&[bytes]).unwrap());
+        let packed = encode_packed(&[bytes]).map_err(|e| format!("Failed to encode: {}", e))?;
+        let encoded = keccak256_ethers(packed);
         Ok(format!("{}", Bytes::from(encoded)).to_string())
     }
 
+    fn hash_typed_data(args: wrap::ArgsHashTypedData) -> Result<String, String> {
+        let typed_data =
+            parse_typed_data(&args.payload).map_err(|e| format!("Invalid typed data: {}", e))?;
+        let hash = utils_hash_typed_data(&typed_data)
+            .map_err(|e| format!("Failed to hash typed data: {}", e))?;
+        Ok(format!("{:?}", hash.digest))
+    }
+
+    fn recover_address(args: wrap::ArgsRecoverAddress) -> Result<String, String> {
+        let digest = H256::from_str(&args.digest).map_err(|e| format!("Invalid digest: {}", e))?;
+        let signature = parse_signature(&args.signature).map_err(|e| e.to_string())?;
+        let address = utils_recover_address(digest, &signature)
+            .map_err(|e| format!("Failed to recover address: {}", e))?;
+        Ok(format!("{:?}", address))
+    }
+
+    fn verify_message(args: wrap::ArgsVerifyMessage) -> Result<String, String> {
+        let signature = parse_signature(&args.signature).map_err(|e| e.to_string())?;
+        let address = utils_verify_message(&args.message, &signature)
+            .map_err(|e| format!("Failed to verify message: {}", e))?;
+        Ok(format!("{:?}", address))
+    }
+
+    fn verify_typed_data(args: wrap::ArgsVerifyTypedData) -> Result<String, String> {
+        let typed_data =
+            parse_typed_data(&args.payload).map_err(|e| format!("Invalid typed data: {}", e))?;
+        let signature = parse_signature(&args.signature).map_err(|e| e.to_string())?;
+        let address = utils_verify_typed_data(&typed_data, &signature)
+            .map_err(|e| format!("Failed to verify typed data: {}", e))?;
+        Ok(format!("{:?}", address))
+    }
+
+    fn recover_transaction_signer(
+        args: wrap::ArgsRecoverTransactionSigner,
+    ) -> Result<String, String> {
+        let raw = Bytes::from_str(&args.raw_tx).map_err(|e| format!("Invalid rawTx: {}", e))?;
+        let address = utils_recover_transaction_signer(&raw)
+            .map_err(|e| format!("Failed to recover transaction signer: {}", e))?;
+        Ok(format!("{:?}", address))
+    }
+
     fn generate_create2_address(args: wrap::ArgsGenerateCreate2Address) -> Result<String, String> {
-        let salt = Bytes::from_str(&args.salt).unwrap();
-        let init_code = Bytes::from_str(&args.init_code).unwrap();
-        let address = args.address.parse::<Address>().unwrap();
+        let salt = Bytes::from_str(&args.salt).map_err(|e| format!("Invalid salt: {}", e))?;
+        let init_code =
+            Bytes::from_str(&args.init_code).map_err(|e| format!("Invalid initCode: {}", e))?;
+        let address = args
+            .address
+            .parse::<Address>()
+            .map_err(|e| format!("Invalid address: {}", e))?;
         let generated_address = get_create2_address(address, salt, init_code);
 
         Ok(format!("{:?}", generated_address))
@@ -47,12 +100,14 @@ impl ModuleTrait for Module {
         }
 
         let operation = Token::FixedBytes(op_bytes.into());
-        let to = args.to.parse::<Address>().unwrap();
+        let to = args.to.parse::<Address>().map_err(|e| format!("Invalid to: {}", e))?;
 
-        let value = utils_encode_params(vec!["uint256".into()], vec![args.value.0.to_string()]);
+        let value = utils_encode_params(vec!["uint256".into()], vec![args.value.0.to_string()])
+            .map_err(|e| format!("Invalid value: {}", e))?;
 
-        let data = Bytes::from_str(&args.data).unwrap();
-        let data_len = utils_encode_params(vec!["uint256".into()], vec![data.len().to_string()]);
+        let data = Bytes::from_str(&args.data).map_err(|e| format!("Invalid data: {}", e))?;
+        let data_len = utils_encode_params(vec!["uint256".into()], vec![data.len().to_string()])
+            .map_err(|e| format!("Invalid data: {}", e))?;
 
         let encoded = encode_packed(&[
             operation,
@@ -61,22 +116,46 @@ impl ModuleTrait for Module {
             Token::Bytes(data_len),
             Token::Bytes(data.to_vec()),
         ])
-        .unwrap();
+        .map_err(|e| format!("Failed to encode meta transaction: {}", e))?;
 
         Ok(format!("{}", Bytes::from(encoded)))
     }
 
     fn encode_params(input: wrap::ArgsEncodeParams) -> Result<String, String> {
-        let bytes: Bytes = utils_encode_params(input.types, input.values).into();
+        let bytes: Bytes = utils_encode_params(input.types, input.values)
+            .map_err(|e| format!("Failed to encode params: {}", e))?
+            .into();
         Ok(format!("{}", bytes))
     }
 
     fn encode_function(input: wrap::ArgsEncodeFunction) -> Result<String, String> {
         let args: Vec<String> = input.args.unwrap_or(vec![]);
-        let (_, bytes): (Function, Bytes) = utils_encode_function(&input.method, &args).unwrap();
+        let (_, bytes): (Function, Bytes) = utils_encode_function(&input.method, &args)
+            .map_err(|e| format!("Failed to encode function: {}", e))?;
         Ok(format!("{}", bytes))
     }
 
+    fn decode_function_data(args: wrap::ArgsDecodeFunctionData) -> Result<String, String> {
+        let data = Bytes::from_str(&args.data).map_err(|e| format!("Invalid data: {}", e))?;
+        let tokens = utils_decode_function(&args.method, data.to_vec())
+            .map_err(|e| format!("Failed to decode function data: {}", e))?;
+        Ok(format_tokens_array(&tokens))
+    }
+
+    fn decode_function_result(args: wrap::ArgsDecodeFunctionResult) -> Result<String, String> {
+        let data = Bytes::from_str(&args.data).map_err(|e| format!("Invalid data: {}", e))?;
+        let tokens = utils_decode_function_result(&args.method, data.to_vec())
+            .map_err(|e| format!("Failed to decode function result: {}", e))?;
+        Ok(format_tokens(&tokens))
+    }
+
+    fn decode_params(args: wrap::ArgsDecodeParams) -> Result<String, String> {
+        let data = Bytes::from_str(&args.data).map_err(|e| format!("Invalid data: {}", e))?;
+        let tokens = utils_decode_params(&args.types, data.to_vec())
+            .map_err(|e| format!("Failed to decode params: {}", e))?;
+        Ok(format_tokens_array(&tokens))
+    }
+
     fn to_wei(input: ArgsToWei) -> Result<String, String> {
         Ok(utils_to_wei(input.eth).to_string())
     }
@@ -88,4 +167,13 @@ impl ModuleTrait for Module {
     fn solidity_pack(args: wrap::ArgsSolidityPack) -> Result<String, String> {
         return utils_solidity_pack(args.types, args.values);
     }
+
+    fn encode_event_topics(args: wrap::ArgsEncodeEventTopics) -> Result<Vec<Option<String>>, String> {
+        let values: Vec<Option<String>> = args.values.unwrap_or(vec![]);
+        let topics = utils_encode_event_topics(&args.event, &values).map_err(|e| e.to_string())?;
+        Ok(topics
+            .into_iter()
+            .map(|topic| topic.map(|v| format!("{:#x}", v)))
+            .collect())
+    }
 }
@@ -490,5 +490,277 @@ describe("Ethereum Wrapper", () => {
         expect(response.value).toEqual(expected);
       });
     });
+
+    describe("encodeEventTopics", () => {
+      const event = "event Transfer(address indexed from, address indexed to, uint256 value)";
+      const from = "0x90F8bf6A479f320ead074411a4B0e7944Ea8c9C1";
+
+      it("should encode topics of a human-readable event", async () => {
+        const response = await client.invoke<(string | null)[]>({
+          uri,
+          method: "encodeEventTopics",
+          args: { event, values: [from] },
+        });
+        if (!response.ok) throw response.error;
+
+        const iface = new utils.Interface([event]);
+        const expected = iface.encodeFilterTopics("Transfer", [from]);
+        expect(response.value).toEqual(expected);
+      });
+
+      it("should encode topics with wildcards", async () => {
+        const response = await client.invoke<(string | null)[]>({
+          uri,
+          method: "encodeEventTopics",
+          args: { event, values: [null, from] },
+        });
+        if (!response.ok) throw response.error;
+
+        const iface = new utils.Interface([event]);
+        const expected = iface.encodeFilterTopics("Transfer", [null, from]);
+        expect(response.value).toEqual(expected);
+      });
+
+      it("should hash indexed dynamic values of a JSON ABI event", async () => {
+        const jsonEvent = JSON.stringify({
+          anonymous: false,
+          inputs: [
+            { indexed: true, name: "from", type: "address" },
+            { indexed: true, name: "ipfsHash", type: "string" },
+          ],
+          name: "HashSet",
+          type: "event",
+        });
+        const response = await client.invoke<(string | null)[]>({
+          uri,
+          method: "encodeEventTopics",
+          args: { event: jsonEvent, values: [null, "QmHash"] },
+        });
+        if (!response.ok) throw response.error;
+
+        expect(response.value).toEqual([
+          utils.id("HashSet(address,string)"),
+          null,
+          utils.id("QmHash"),
+        ]);
+      });
+    });
+
+    describe("typed data", () => {
+      const domain = {
+        name: "Ether Mail",
+        version: "1",
+        chainId: 1,
+        verifyingContract: "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+      };
+      const types = {
+        Person: [
+          { name: "name", type: "string" },
+          { name: "wallet", type: "address" },
+        ],
+        Mail: [
+          { name: "from", type: "Person" },
+          { name: "to", type: "Person" },
+          { name: "contents", type: "string" },
+        ],
+      };
+      const message = {
+        from: { name: "Cow", wallet: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+        to: { name: "Bob", wallet: "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+        contents: "Hello, Bob!",
+      };
+      const payload = {
+        domain,
+        primaryType: "Mail",
+        types: {
+          EIP712Domain: [
+            { name: "name", type: "string" },
+            { name: "version", type: "string" },
+            { name: "chainId", type: "uint256" },
+            { name: "verifyingContract", type: "address" },
+          ],
+          ...types,
+        },
+        message,
+      };
+
+      it("should hash typed data", async () => {
+        const response = await client.invoke<string>({
+          uri,
+          method: "hashTypedData",
+          args: { payload: JSON.stringify(payload) },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(utils._TypedDataEncoder.hash(domain, types, message));
+        expect(response.value).toBe(
+          "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+      });
+
+      it("should fail on invalid typed data", async () => {
+        const response = await client.invoke<string>({
+          uri,
+          method: "hashTypedData",
+          args: { payload: JSON.stringify({ message }) },
+        });
+        expect(response.ok).toBe(false);
+      });
+    });
+
+    describe("signature verification", () => {
+      const wallet = new ethers.Wallet(
+        "0x4f3edf983ac636a65a842ce7c78d9aa706d3b113bce9c46f30d7d21715b23b1d"
+      );
+      const address = wallet.address.toLowerCase();
+
+      it("recoverAddress", async () => {
+        const digest = utils.keccak256(utils.toUtf8Bytes("Hello World"));
+        const signature = utils.joinSignature(wallet._signingKey().signDigest(digest));
+        const response = await client.invoke<string>({
+          uri,
+          method: "recoverAddress",
+          args: { digest, signature },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(address);
+      });
+
+      it("verifyMessage", async () => {
+        const signature = await wallet.signMessage("Hello World");
+        const response = await client.invoke<string>({
+          uri,
+          method: "verifyMessage",
+          args: { message: "Hello World", signature },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(address);
+      });
+
+      it("verifyTypedData", async () => {
+        const domain = { name: "Ether Mail", version: "1", chainId: 1 };
+        const types = { Person: [{ name: "name", type: "string" }] };
+        const message = { name: "Cow" };
+        const signature = await wallet._signTypedData(domain, types, message);
+        const payload = {
+          domain,
+          primaryType: "Person",
+          types: {
+            EIP712Domain: [
+              { name: "name", type: "string" },
+              { name: "version", type: "string" },
+              { name: "chainId", type: "uint256" },
+            ],
+            ...types,
+          },
+          message,
+        };
+        const response = await client.invoke<string>({
+          uri,
+          method: "verifyTypedData",
+          args: { payload: JSON.stringify(payload), signature },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe(address);
+      });
+
+      it("recoverTransactionSigner", async () => {
+        const legacy = await wallet.signTransaction({
+          to: address,
+          value: 1,
+          gasLimit: 21000,
+          gasPrice: 1000000000,
+          nonce: 0,
+          chainId: 1337,
+        });
+        const eip1559 = await wallet.signTransaction({
+          type: 2,
+          to: address,
+          value: 1,
+          gasLimit: 21000,
+          maxFeePerGas: 2000000000,
+          maxPriorityFeePerGas: 1000000000,
+          nonce: 1,
+          chainId: 1337,
+        });
+
+        for (const rawTx of [legacy, eip1559]) {
+          const response = await client.invoke<string>({
+            uri,
+            method: "recoverTransactionSigner",
+            args: { rawTx },
+          });
+          if (!response.ok) throw response.error;
+          expect(response.value).toBe(address);
+        }
+      });
+
+      it("should fail on invalid signature", async () => {
+        const response = await client.invoke<string>({
+          uri,
+          method: "verifyMessage",
+          args: { message: "Hello World", signature: "0x1234" },
+        });
+        expect(response.ok).toBe(false);
+      });
+    });
+
+    describe("decoding", () => {
+      const method = "function transfer(address to, uint256 amount) returns (bool)";
+      const to = "0x90f8bf6a479f320ead074411a4b0e7944ea8c9c1";
+      const iface = new utils.Interface([method]);
+
+      it("decodeFunctionData", async () => {
+        const data = iface.encodeFunctionData("transfer", [to, "1000"]);
+        const response = await client.invoke<string>({
+          uri,
+          method: "decodeFunctionData",
+          args: { method, data },
+        });
+        if (!response.ok) throw response.error;
+        expect(JSON.parse(response.value)).toEqual([to, 1000]);
+      });
+
+      it("decodeFunctionData - short data", async () => {
+        const response = await client.invoke<string>({
+          uri,
+          method: "decodeFunctionData",
+          args: { method, data: "0xa905" },
+        });
+        expect(response.ok).toBe(false);
+        if (response.ok) throw Error("should never happen");
+        expect(response.error?.message).toContain("Failed to decode function data");
+      });
+
+      it("decodeFunctionResult", async () => {
+        const data = iface.encodeFunctionResult("transfer", [true]);
+        const response = await client.invoke<string>({
+          uri,
+          method: "decodeFunctionResult",
+          args: { method, data },
+        });
+        if (!response.ok) throw response.error;
+        expect(response.value).toBe("true");
+      });
+
+      it("decodeParams", async () => {
+        const types = ["string", "uint256[]", "(address,bool)"];
+        const data = utils.defaultAbiCoder.encode(types, [
+          "hello",
+          ["1", "115792089237316195423570985008687907853269984665640564039457584007913129639935"],
+          [to, true],
+        ]);
+        const response = await client.invoke<string>({
+          uri,
+          method: "decodeParams",
+          args: { types, data },
+        });
+        if (!response.ok) throw response.error;
+        expect(JSON.parse(response.value)).toEqual([
+          "hello",
+          [1, "115792089237316195423570985008687907853269984665640564039457584007913129639935"],
+          [to, true],
+        ]);
+      });
+    });
   });
 });